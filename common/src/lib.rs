@@ -0,0 +1,50 @@
+//! Command line plumbing shared by the day crates.
+
+use std::io::IsTerminal;
+
+/// Sets up diagnostics on stderr, keeping stdout for answers, and returns `args` without the
+/// verbosity flags. Each `-v` (or a `-vv` style run) raises the level from warnings to debug and
+/// then trace; `RUST_LOG`, when set, takes precedence.
+pub fn init_tracing(args: Vec<String>) -> Vec<String> {
+    let (flags, rest) = split_verbosity(args);
+
+    let level = match flags {
+        0 => "warn",
+        1 => "debug",
+        _ => "trace",
+    };
+    let filter = tracing_subscriber::EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new(level));
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .with_ansi(std::io::stderr().is_terminal())
+        .init();
+
+    rest
+}
+
+/// Returns how many `v`s the `-v` style flags in `args` add up to, and the other arguments.
+fn split_verbosity(args: Vec<String>) -> (usize, Vec<String>) {
+    let (flags, rest): (Vec<String>, Vec<String>) = args
+        .into_iter()
+        .partition(|a| a.len() > 1 && a.starts_with('-') && a[1..].chars().all(|c| c == 'v'));
+    (flags.iter().map(|a| a.len() - 1).sum(), rest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verbosity_flags() {
+        let args = |a: &[&str]| a.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+        assert_eq!(split_verbosity(args(&[])), (0, vec![]));
+        assert_eq!(
+            split_verbosity(args(&["-v", "--check", "-vv", "file"])),
+            (3, args(&["--check", "file"]))
+        );
+        // A lone `-` is stdin for some tools, not a flag
+        assert_eq!(split_verbosity(args(&["-", "-x"])), (0, args(&["-", "-x"])));
+    }
+}
@@ -0,0 +1,110 @@
+//! Throughput of the streaming part 1 solver on a generated calibration file.
+//!
+//! Writes `AOC_BENCH_BYTES` bytes (2 GiB by default) of random calibration lines to the temp
+//! directory, then times `stream::sum_calibration` over it. The line-by-line scanner is timed on
+//! the first `AOC_BENCH_SCANNER_BYTES` (64 MiB by default) for comparison. Set `AOC_BENCH_KEEP`
+//! to leave the file behind for repeated runs.
+//!
+//! Run with `cargo bench --bench stream`.
+
+use day_01::Options;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::time::Instant;
+
+const LETTERS: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+
+fn env_bytes(name: &str, default: u64) -> u64 {
+    std::env::var(name)
+        .ok()
+        .map(|a| a.parse().expect("Expected a byte count"))
+        .unwrap_or(default)
+}
+
+/// Tiny xorshift so the file is reproducible without pulling in `rand`.
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+/// Writes lines of 20 to 60 random letters with a few digits sprinkled in.
+fn generate(path: &Path, size: u64) {
+    let mut out = BufWriter::with_capacity(1 << 20, File::create(path).expect("create"));
+    let mut rng = XorShift(0x2023_1201);
+    let mut written = 0;
+    let mut line = Vec::with_capacity(64);
+
+    while written < size {
+        line.clear();
+        let len = 20 + rng.next() % 41;
+        for _ in 0..len {
+            let r = rng.next();
+            match r % 8 {
+                0 => line.push(b'0' + (r >> 8) as u8 % 10),
+                _ => line.push(LETTERS[(r >> 8) as usize % LETTERS.len()]),
+            }
+        }
+        // Every line needs at least one digit
+        line.push(b'0' + rng.next() as u8 % 10);
+        line.push(b'\n');
+        out.write_all(&line).expect("write");
+        written += line.len() as u64;
+    }
+}
+
+fn report(name: &str, bytes: u64, start: Instant, answer: u64) {
+    let secs = start.elapsed().as_secs_f64();
+    let gib = bytes as f64 / (1u64 << 30) as f64;
+    println!(
+        "{name:>8}: {gib:.2} GiB in {secs:.2}s = {:.2} GiB/s (sum {answer})",
+        gib / secs
+    );
+}
+
+fn main() {
+    let size = env_bytes("AOC_BENCH_BYTES", 2 << 30);
+    let scanner_size = env_bytes("AOC_BENCH_SCANNER_BYTES", 64 << 20).min(size);
+    let path = std::env::temp_dir().join(format!("day-01-calibration-{size}.txt"));
+
+    if !path.exists() {
+        let start = Instant::now();
+        generate(&path, size);
+        println!(
+            "generated {} in {:.2}s",
+            path.display(),
+            start.elapsed().as_secs_f64()
+        );
+    }
+    let bytes = std::fs::metadata(&path).expect("metadata").len();
+
+    let start = Instant::now();
+    let reader = BufReader::with_capacity(1 << 20, File::open(&path).expect("open"));
+    let answer = day_01::stream::sum_calibration(reader).expect("stream");
+    report("stream", bytes, start, answer);
+
+    // The scanner needs whole lines in memory, so give it a prefix cut at a line boundary
+    let mut prefix = String::new();
+    let mut reader = BufReader::new(File::open(&path).expect("open")).take(scanner_size);
+    reader.read_to_string(&mut prefix).expect("read");
+    prefix.truncate(prefix.rfind('\n').map_or(0, |a| a + 1));
+    let prefix_bytes = prefix.len() as u64;
+
+    let start = Instant::now();
+    let answer = day_01::solve(&prefix, &Options::PART_1) as u64;
+    report("scanner", prefix_bytes, start, answer);
+
+    let start = Instant::now();
+    let answer = day_01::stream::sum_calibration(prefix.as_bytes()).expect("stream");
+    report("stream", prefix_bytes, start, answer);
+
+    if std::env::var_os("AOC_BENCH_KEEP").is_none() {
+        std::fs::remove_file(&path).expect("remove");
+    }
+}
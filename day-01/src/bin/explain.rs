@@ -0,0 +1,49 @@
+//! Per-line explanation of the day 1 calibration values.
+//!
+//! Runs the same scanner as `part1` and `part2` and prints, for every line, the first and last
+//! tokens it found and the value they produce. Useful when the answer is off and the culprit is
+//! one odd line.
+//!
+//! Usage: `explain [--part <1|2>] [--color] [--input <file>] [scanner flags]`, where the scanner
+//! flags are the same `--words`, `--first`, `--last` and `--unicode` understood by the parts.
+
+use day_01::Options;
+
+fn main() {
+    let mut options = Options::PART_2;
+    let mut color = false;
+    let mut path = None;
+    let mut scanner_args = vec![];
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--part" => match args.next().as_deref() {
+                Some("1") => options = Options::PART_1,
+                Some("2") => options = Options::PART_2,
+                other => panic!("Expected --part 1 or 2, got {other:?}"),
+            },
+            "--color" => color = true,
+            "--input" => path = Some(args.next().expect("Missing value for --input")),
+            _ => scanner_args.push(arg),
+        }
+    }
+
+    // Scanner flags are applied after --part so they can tweak either part
+    let options = options
+        .with_args(scanner_args)
+        .unwrap_or_else(|e| panic!("{e}"));
+
+    let input = match path {
+        Some(path) => std::fs::read_to_string(path).expect("Failed to read input"),
+        None => include_str!("./input.txt").to_string(),
+    };
+
+    for (number, line) in input.lines().enumerate() {
+        println!(
+            "{:>4}: {}",
+            number + 1,
+            day_01::explain(line, &options, color)
+        );
+    }
+}
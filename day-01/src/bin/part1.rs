@@ -1,6 +1,10 @@
-/// Solution for Advent of Code 2023, day 1, part 1.
-///
-/// Refactor heavily supported by: https://www.youtube.com/watch?v=JOgQMjpGum0
+//! Solution for Advent of Code 2023, day 1, part 1.
+//!
+//! Refactor heavily supported by: https://www.youtube.com/watch?v=JOgQMjpGum0
+
+use day_01::Options;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
 
 /// Returns a number constructed from the first and last digits
 ///
@@ -8,30 +12,56 @@
 ///
 /// - `'abc1sd3'` -> `Some(13)`
 /// - `'fhcg5s'` -> `Some(55)`
-fn parse_line(input: &str) -> u32 {
-    let mut nums = input.chars().filter(|c| c.is_digit(10));
-
-    let first = nums.next().expect("Input must contain at least one digit.");
-    let last = nums.last();
-
-    match (first, last) {
-        (f, Some(l)) => format!("{f}{l}").parse().expect("Failed to parse."),
-        (f, None) => format!("{f}{f}").parse().expect("Failed to parse."),
-    }
+fn parse_line(input: &str, options: &Options) -> u32 {
+    day_01::calibration_value(input, options).expect("Input must contain at least one digit.")
 }
 
-/// Returns the sum of the calibration values parsed from each line
+/// Returns the sum of the calibration values parsed from each line of `reader`
 ///
 /// # Arguments
 ///
-/// * `input` - `'\n'` separated string slice to parse calibration values from
-fn solve(input: &str) -> u32 {
-    input.lines().map(parse_line).sum()
+/// * `reader` - `'\n'` separated calibration lines
+/// * `options` - pass `--unicode` on the command line to accept digits from every script
+fn solve(mut reader: impl BufRead, options: &Options) -> u64 {
+    // Plain ASCII digits take the streaming fast path, anything fancier needs the scanner
+    if *options == Options::PART_1 {
+        return day_01::stream::sum_calibration(reader).expect("Failed to read input");
+    }
+
+    let mut input = String::new();
+    reader
+        .read_to_string(&mut input)
+        .expect("Failed to read input");
+    input
+        .lines()
+        .map(|line| parse_line(line, options) as u64)
+        .sum()
 }
 
 fn main() {
-    let input = include_str!("./input.txt");
-    print!("{}\n", solve(input));
+    let mut path = None;
+    let mut scanner_args = vec![];
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" => path = Some(args.next().expect("Missing value for --input")),
+            _ => scanner_args.push(arg),
+        }
+    }
+
+    let options = Options::PART_1
+        .with_args(scanner_args)
+        .unwrap_or_else(|e| panic!("{e}"));
+
+    let answer = match path {
+        Some(path) => solve(
+            BufReader::new(File::open(path).expect("Failed to open input")),
+            &options,
+        ),
+        None => solve(include_str!("./input.txt").as_bytes(), &options),
+    };
+    println!("{answer}");
 }
 
 #[cfg(test)]
@@ -40,15 +70,34 @@ mod tests {
 
     #[test]
     fn line_parser() {
-        assert_eq!(parse_line("treb7uchet"), 77);
-        assert_eq!(parse_line("1abc2"), 12);
-        assert_eq!(parse_line("pqr3stu8vwx"), 38);
-        assert_eq!(parse_line("a1b2c3d4e5f"), 15);
+        assert_eq!(parse_line("treb7uchet", &Options::PART_1), 77);
+        assert_eq!(parse_line("1abc2", &Options::PART_1), 12);
+        assert_eq!(parse_line("pqr3stu8vwx", &Options::PART_1), 38);
+        assert_eq!(parse_line("a1b2c3d4e5f", &Options::PART_1), 15);
+    }
+
+    #[test]
+    fn unicode_line_parser() {
+        let unicode = Options {
+            unicode: true,
+            ..Options::PART_1
+        };
+        assert_eq!(parse_line("tre٣b7uchet", &unicode), 37);
+        assert_eq!(parse_line("１abc२", &unicode), 12);
+    }
+
+    #[test]
+    fn fast_path_matches_scanner() {
+        let input = include_str!("./input.txt");
+        assert_eq!(
+            day_01::stream::sum_calibration(input.as_bytes()).unwrap(),
+            day_01::solve(input, &Options::PART_1) as u64
+        );
     }
 
     #[test]
     fn full_puzzle_input() {
         let input = include_str!("./input.txt");
-        assert_eq!(solve(input), 54877);
+        assert_eq!(solve(input.as_bytes(), &Options::PART_1), 54877);
     }
 }
@@ -1,6 +1,8 @@
-/// Solution for Advent of Code 2023, day 1, part 2.
-///
-/// Refactor supported by tips from AOC Reddit after initial solution implementation
+//! Solution for Advent of Code 2023, day 1, part 2.
+//!
+//! Refactor supported by tips from AOC Reddit after initial solution implementation
+
+use day_01::Options;
 
 /// Returns a number constructed from the first and last digits
 ///
@@ -8,39 +10,24 @@
 ///
 /// - `'abc1sd3'` -> `Some(13)`
 /// - `'fhcg5s'` -> `Some(55)`
-fn parse_line(input: &str) -> u32 {
+fn parse_line(input: &str, options: &Options) -> u32 {
     // On release day, the serious snake in the grass was handing overlaps such as "twone" which
-    // should become 21. A neat trick is to just embed the desired digit into the middle of the
-    // actual word spelling so both get parsed.
-    let sanitized = input
-        .replace("one", "o1e")
-        .replace("two", "t2o")
-        .replace("three", "thr3ee")
-        .replace("four", "fo4ur")
-        .replace("five", "fi5ve")
-        .replace("six", "s6x")
-        .replace("seven", "se7en")
-        .replace("eight", "ei8ght")
-        .replace("nine", "ni9ne");
-
-    let mut nums = sanitized.chars().filter(|c| c.is_digit(10));
-
-    let first = nums.next().expect("Input must contain at least one digit.");
-    let last = nums.last();
-
-    match (first, last) {
-        (f, Some(l)) => format!("{f}{l}").parse().expect("Failed to parse."),
-        (f, None) => format!("{f}{f}").parse().expect("Failed to parse."),
-    }
+    // should become 21. The scanner tries a match at every position, so both words are found.
+    day_01::calibration_value(input, options).expect("Input must contain at least one digit.")
 }
 
-fn solve(input: &str) -> u32 {
-    input.lines().map(parse_line).sum()
+/// Returns the sum of the calibration values, with the word rules adjustable from the command
+/// line, e.g. `--words compound --last token`.
+fn solve(input: &str, options: &Options) -> u32 {
+    input.lines().map(|line| parse_line(line, options)).sum()
 }
 
 fn main() {
+    let options = Options::PART_2
+        .with_args(std::env::args().skip(1))
+        .unwrap_or_else(|e| panic!("{e}"));
     let input = include_str!("./input.txt");
-    print!("{}\n", solve(input));
+    println!("{}", solve(input, &options));
 }
 
 #[cfg(test)]
@@ -49,22 +36,22 @@ mod tests {
 
     #[test]
     fn line_parser() {
-        assert_eq!(parse_line("treb7uchet"), 77);
-        assert_eq!(parse_line("1abc2"), 12);
-        assert_eq!(parse_line("pqr3stu8vwx"), 38);
-        assert_eq!(parse_line("a1b2c3d4e5f"), 15);
-        assert_eq!(parse_line("xtwone3four"), 24);
-        assert_eq!(parse_line("4nineeightseven2"), 42);
-        assert_eq!(parse_line("zoneight234"), 14);
-        assert_eq!(parse_line("7pqrstsixteen"), 76);
-        assert_eq!(parse_line("one"), 11);
+        assert_eq!(parse_line("treb7uchet", &Options::PART_2), 77);
+        assert_eq!(parse_line("1abc2", &Options::PART_2), 12);
+        assert_eq!(parse_line("pqr3stu8vwx", &Options::PART_2), 38);
+        assert_eq!(parse_line("a1b2c3d4e5f", &Options::PART_2), 15);
+        assert_eq!(parse_line("xtwone3four", &Options::PART_2), 24);
+        assert_eq!(parse_line("4nineeightseven2", &Options::PART_2), 42);
+        assert_eq!(parse_line("zoneight234", &Options::PART_2), 14);
+        assert_eq!(parse_line("7pqrstsixteen", &Options::PART_2), 76);
+        assert_eq!(parse_line("one", &Options::PART_2), 11);
         // Fear yee
-        assert_eq!(parse_line("twone"), 21);
+        assert_eq!(parse_line("twone", &Options::PART_2), 21);
     }
 
     #[test]
     fn full_puzzle_input() {
         let input = include_str!("./input.txt");
-        assert_eq!(solve(input), 54100);
+        assert_eq!(solve(input, &Options::PART_2), 54100);
     }
 }
@@ -0,0 +1,518 @@
+//! Shared calibration value scanner for Advent of Code 2023, day 1.
+//!
+//! Both parts boil down to "find the first and last number-ish thing on a line". The scanner
+//! walks every char boundary of a line and records each digit or number word starting there, so
+//! overlapping spellings such as `"twone"` produce both `two` and `one`.
+
+use std::str::FromStr;
+
+pub mod stream;
+
+/// The zero of every run of decimal digits (general category `Nd`) as of Unicode 17.0. Unicode
+/// guarantees each run is ten contiguous code points in order, so a digit's value is its offset
+/// from the zero before it.
+const DECIMAL_ZEROS: [u32; 77] = [
+    0x0030, 0x0660, 0x06F0, 0x07C0, 0x0966, 0x09E6, 0x0A66, 0x0AE6, 0x0B66, 0x0BE6, 0x0C66, 0x0CE6,
+    0x0D66, 0x0DE6, 0x0E50, 0x0ED0, 0x0F20, 0x1040, 0x1090, 0x17E0, 0x1810, 0x1946, 0x19D0, 0x1A80,
+    0x1A90, 0x1B50, 0x1BB0, 0x1C40, 0x1C50, 0xA620, 0xA8D0, 0xA900, 0xA9D0, 0xA9F0, 0xAA50, 0xABF0,
+    0xFF10, 0x104A0, 0x10D30, 0x10D40, 0x11066, 0x110F0, 0x11136, 0x111D0, 0x112F0, 0x11450,
+    0x114D0, 0x11650, 0x116C0, 0x116D0, 0x116DA, 0x11730, 0x118E0, 0x11950, 0x11BF0, 0x11C50,
+    0x11D50, 0x11DA0, 0x11DE0, 0x11F50, 0x16130, 0x16A60, 0x16AC0, 0x16B50, 0x16D70, 0x1CCF0,
+    0x1D7CE, 0x1D7D8, 0x1D7E2, 0x1D7EC, 0x1D7F6, 0x1E140, 0x1E2F0, 0x1E4F0, 0x1E5F1, 0x1E950,
+    0x1FBF0,
+];
+
+const ONES: [&str; 9] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+const TEENS: [&str; 10] = [
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+
+const TENS: [&str; 8] = [
+    "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+
+/// Which spelled out numbers are recognised alongside plain digits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Words {
+    /// Digits only, as in part 1.
+    Off,
+    /// `one` through `nine`, one digit each, as in part 2.
+    Simple,
+    /// Compound English numbers such as `eleven`, `twenty-three`, `fortytwo` and `hundred`.
+    Compound,
+}
+
+/// How much of the first or last token contributes to the calibration value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    /// A single digit: the leading digit of the first token or the trailing digit of the last.
+    Digit,
+    /// Every digit of the token, so `twenty-three` contributes `23`.
+    Token,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Digit,
+    Word,
+}
+
+/// A number found in a line, with the byte range it was spelled in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token {
+    pub value: u32,
+    pub start: usize,
+    pub end: usize,
+    pub kind: TokenKind,
+}
+
+/// Knobs for running "what if" variants of the calibration puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    pub words: Words,
+    pub first: Part,
+    pub last: Part,
+    /// Accept decimal digits from every script, not just ASCII `0`-`9`.
+    pub unicode: bool,
+}
+
+impl Options {
+    pub const PART_1: Options = Options {
+        words: Words::Off,
+        first: Part::Digit,
+        last: Part::Digit,
+        unicode: false,
+    };
+
+    pub const PART_2: Options = Options {
+        words: Words::Simple,
+        first: Part::Digit,
+        last: Part::Digit,
+        unicode: false,
+    };
+
+    /// Applies `--words <off|simple|compound>`, `--first <digit|token>`, `--last <digit|token>`
+    /// and `--unicode` flags on top of `self`.
+    pub fn with_args(mut self, args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut args = args.into_iter();
+        while let Some(flag) = args.next() {
+            let mut value = || args.next().ok_or(format!("Missing value for {flag}"));
+            match flag.as_str() {
+                "--words" => self.words = value()?.parse()?,
+                "--first" => self.first = value()?.parse()?,
+                "--last" => self.last = value()?.parse()?,
+                "--unicode" => self.unicode = true,
+                _ => return Err(format!("Unknown argument: {flag}")),
+            }
+        }
+        Ok(self)
+    }
+}
+
+impl FromStr for Words {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "off" => Ok(Words::Off),
+            "simple" => Ok(Words::Simple),
+            "compound" => Ok(Words::Compound),
+            _ => Err(format!("Unknown word mode: {s}")),
+        }
+    }
+}
+
+impl FromStr for Part {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "digit" => Ok(Part::Digit),
+            "token" => Ok(Part::Token),
+            _ => Err(format!("Unknown part rule: {s}")),
+        }
+    }
+}
+
+/// Returns the value of a decimal digit from any script, e.g. `'٣'` -> `Some(3)`.
+pub fn unicode_digit(c: char) -> Option<u32> {
+    let c = c as u32;
+    let zero = DECIMAL_ZEROS[DECIMAL_ZEROS.partition_point(|&z| z <= c).checked_sub(1)?];
+    (c - zero < 10).then_some(c - zero)
+}
+
+/// Returns the index and byte length of the first entry of `words` that `input` starts with.
+fn match_list(input: &str, words: &[&str]) -> Option<(usize, usize)> {
+    words
+        .iter()
+        .position(|w| input.starts_with(w))
+        .map(|i| (i, words[i].len()))
+}
+
+/// Matches `one` through `nine`.
+fn match_one(input: &str) -> Option<(u32, usize)> {
+    match_list(input, &ONES).map(|(i, len)| (i as u32 + 1, len))
+}
+
+/// Matches any number below one hundred, e.g. `seven`, `seventeen`, `seventy-seven`.
+fn match_below_hundred(input: &str) -> Option<(u32, usize)> {
+    // Teens go first so "seventeen" isn't read as "seven"
+    if let Some((i, len)) = match_list(input, &TEENS) {
+        return Some((i as u32 + 10, len));
+    }
+
+    if let Some((i, len)) = match_list(input, &TENS) {
+        let tens = (i as u32 + 2) * 10;
+        return match match_joined(&input[len..], match_one) {
+            Some((ones, rest)) => Some((tens + ones, len + rest)),
+            None => Some((tens, len)),
+        };
+    }
+
+    match_one(input)
+}
+
+/// Runs `matcher` directly on `input` or after a single joining `-`.
+fn match_joined(
+    input: &str,
+    matcher: impl Fn(&str) -> Option<(u32, usize)>,
+) -> Option<(u32, usize)> {
+    match input.strip_prefix('-') {
+        Some(rest) => matcher(rest).map(|(value, len)| (value, len + 1)),
+        None => matcher(input),
+    }
+}
+
+/// Matches `hundred`.
+fn match_hundred(input: &str) -> Option<(u32, usize)> {
+    input
+        .starts_with("hundred")
+        .then_some((100, "hundred".len()))
+}
+
+/// Matches a compound number up to `nine hundred ninety-nine`. A bare `hundred` is 100.
+fn match_compound(input: &str) -> Option<(u32, usize)> {
+    let multiplied = match_one(input).and_then(|(ones, len)| {
+        match_joined(&input[len..], match_hundred).map(|(_, rest)| (ones * 100, len + rest))
+    });
+
+    let (hundreds, len) = match multiplied.or_else(|| match_hundred(input)) {
+        Some(a) => a,
+        None => return match_below_hundred(input),
+    };
+
+    match match_joined(&input[len..], match_below_hundred) {
+        Some((rest, rest_len)) => Some((hundreds + rest, len + rest_len)),
+        None => Some((hundreds, len)),
+    }
+}
+
+/// Returns every digit and number word in `line`, ordered by starting byte offset.
+///
+/// # Examples
+///
+/// - `'xtwone3'` -> `two`, `one`, `3`
+pub fn tokens(line: &str, options: &Options) -> Vec<Token> {
+    let mut found = vec![];
+
+    for (start, c) in line.char_indices() {
+        let digit = match options.unicode {
+            true => unicode_digit(c),
+            false => c.to_digit(10),
+        };
+
+        if let Some(value) = digit {
+            found.push(Token {
+                value,
+                start,
+                end: start + c.len_utf8(),
+                kind: TokenKind::Digit,
+            });
+            continue;
+        }
+
+        let word = match options.words {
+            Words::Off => None,
+            Words::Simple => match_one(&line[start..]),
+            Words::Compound => match_compound(&line[start..]),
+        };
+
+        if let Some((value, len)) = word {
+            found.push(Token {
+                value,
+                start,
+                end: start + len,
+                kind: TokenKind::Word,
+            });
+        }
+    }
+
+    found
+}
+
+/// Returns the first and last tokens of a line. The last token is the one ending furthest right,
+/// preferring the longest spelling when several end together (`twentythree` over `three`).
+pub fn first_and_last(line: &str, options: &Options) -> Option<(Token, Token)> {
+    let found = tokens(line, options);
+    let first = *found.first()?;
+    let last = *found
+        .iter()
+        .max_by_key(|t| (t.end, t.end - t.start))
+        .unwrap();
+    Some((first, last))
+}
+
+/// Returns the number of decimal digits in `value`.
+fn digit_count(value: u32) -> u32 {
+    value.checked_ilog10().unwrap_or(0) + 1
+}
+
+/// Combines the first and last tokens into a calibration value according to `options`.
+pub fn combine(first: &Token, last: &Token, options: &Options) -> u32 {
+    let head = match options.first {
+        Part::Digit => first.value / 10u32.pow(digit_count(first.value) - 1),
+        Part::Token => first.value,
+    };
+    let tail = match options.last {
+        Part::Digit => last.value % 10,
+        Part::Token => last.value,
+    };
+
+    head * 10u32.pow(digit_count(tail)) + tail
+}
+
+/// Returns the calibration value of a line, `None` if the line has no tokens.
+///
+/// # Examples
+///
+/// - `'abc1sd3'` -> `Some(13)`
+/// - `'fhcg5s'` -> `Some(55)`
+pub fn calibration_value(line: &str, options: &Options) -> Option<u32> {
+    first_and_last(line, options).map(|(first, last)| combine(&first, &last, options))
+}
+
+const GREEN: &str = "\x1b[32m";
+const CYAN: &str = "\x1b[36m";
+const RESET: &str = "\x1b[0m";
+
+/// Returns `line` with the first token wrapped in green and the last in cyan. Where the two
+/// overlap, as in `"twone"`, the shared bytes stay green.
+pub fn highlight(line: &str, first: &Token, last: &Token) -> String {
+    let mid = last.start.max(first.end);
+    let end = last.end.max(mid);
+    let tail = match mid == end {
+        true => String::new(),
+        false => format!("{CYAN}{}{RESET}", &line[mid..end]),
+    };
+    format!(
+        "{}{GREEN}{}{RESET}{}{tail}{}",
+        &line[..first.start],
+        &line[first.start..first.end],
+        &line[first.end..mid],
+        &line[end..],
+    )
+}
+
+/// Describes a single token, e.g. `word "six" @6..9`.
+fn describe_token(line: &str, token: &Token) -> String {
+    let kind = match token.kind {
+        TokenKind::Digit => "digit",
+        TokenKind::Word => "word",
+    };
+    format!(
+        "{kind} {:?} @{}..{}",
+        &line[token.start..token.end],
+        token.start,
+        token.end
+    )
+}
+
+/// Explains how the calibration value of `line` is found: the first and last tokens with their
+/// byte offsets, and the resulting value.
+///
+/// # Examples
+///
+/// - `'7pqrstsixteen'` -> `7pqrstsixteen | first digit "7" @0..1 | last word "six" @6..9 | 76`
+pub fn explain(line: &str, options: &Options, color: bool) -> String {
+    let (first, last) = match first_and_last(line, options) {
+        Some(a) => a,
+        None => return format!("{line} | no digits"),
+    };
+
+    let shown = match color {
+        true => highlight(line, &first, &last),
+        false => line.to_string(),
+    };
+
+    format!(
+        "{shown} | first {} | last {} | {}",
+        describe_token(line, &first),
+        describe_token(line, &last),
+        combine(&first, &last, options)
+    )
+}
+
+/// Returns the sum of the calibration values parsed from each line
+///
+/// # Arguments
+///
+/// * `input` - `'\n'` separated string slice to parse calibration values from
+/// * `options` - which tokens count and how much of them is used
+pub fn solve(input: &str, options: &Options) -> u32 {
+    input
+        .lines()
+        .map(|line| {
+            calibration_value(line, options).expect("Input must contain at least one digit.")
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMPOUND: Options = Options {
+        words: Words::Compound,
+        first: Part::Token,
+        last: Part::Token,
+        unicode: false,
+    };
+
+    #[test]
+    fn compound_words() {
+        assert_eq!(match_compound("eleven"), Some((11, 6)));
+        assert_eq!(match_compound("twenty-three"), Some((23, 12)));
+        assert_eq!(match_compound("fortytwo"), Some((42, 8)));
+        assert_eq!(match_compound("forty-"), Some((40, 5)));
+        assert_eq!(match_compound("hundred"), Some((100, 7)));
+        assert_eq!(match_compound("sevenhundred-twelve"), Some((712, 19)));
+        assert_eq!(match_compound("two-hundredninetynine"), Some((299, 21)));
+        assert_eq!(match_compound("seventeen"), Some((17, 9)));
+        assert_eq!(match_compound("sevenx"), Some((7, 5)));
+        assert_eq!(match_compound("xseven"), None);
+    }
+
+    #[test]
+    fn token_rules() {
+        assert_eq!(calibration_value("eleven", &COMPOUND), Some(1111));
+        assert_eq!(calibration_value("a7btwenty-three", &COMPOUND), Some(723));
+        assert_eq!(calibration_value("twentythree", &COMPOUND), Some(2323));
+
+        let digits = Options {
+            words: Words::Compound,
+            ..Options::PART_2
+        };
+        assert_eq!(calibration_value("fortytwo", &digits), Some(42));
+        assert_eq!(calibration_value("hundred9", &digits), Some(19));
+
+        let mixed = Options {
+            first: Part::Digit,
+            ..COMPOUND
+        };
+        assert_eq!(calibration_value("eleven5twelve", &mixed), Some(112));
+    }
+
+    #[test]
+    fn matches_original_parts() {
+        assert_eq!(calibration_value("a1b2c3d4e5f", &Options::PART_1), Some(15));
+        assert_eq!(calibration_value("xtwone3four", &Options::PART_1), Some(33));
+        assert_eq!(calibration_value("xtwone3four", &Options::PART_2), Some(24));
+        assert_eq!(calibration_value("twone", &Options::PART_2), Some(21));
+        assert_eq!(calibration_value("nope", &Options::PART_2), None);
+    }
+
+    #[test]
+    fn unicode_digits() {
+        assert_eq!(unicode_digit('7'), Some(7));
+        assert_eq!(unicode_digit('٣'), Some(3));
+        assert_eq!(unicode_digit('७'), Some(7));
+        assert_eq!(unicode_digit('７'), Some(7));
+        assert_eq!(unicode_digit('𝟘'), Some(0));
+        assert_eq!(unicode_digit('𝟡'), Some(9));
+        assert_eq!(unicode_digit('/'), None);
+        assert_eq!(unicode_digit('a'), None);
+        assert_eq!(unicode_digit('²'), None);
+        assert_eq!(unicode_digit('Ⅻ'), None);
+        assert_eq!(unicode_digit('\u{0}'), None);
+    }
+
+    #[test]
+    fn mixed_script_lines() {
+        let unicode = Options {
+            unicode: true,
+            ..Options::PART_2
+        };
+        assert_eq!(calibration_value("ab٣cd", &unicode), Some(33));
+        assert_eq!(calibration_value("x٣y７z", &unicode), Some(37));
+        assert_eq!(calibration_value("९nine", &unicode), Some(99));
+        assert_eq!(calibration_value("one２三", &unicode), Some(12));
+        assert_eq!(calibration_value("٣abc", &Options::PART_2), None);
+
+        let token = tokens("a７b", &unicode)[0];
+        assert_eq!((token.start, token.end), (1, 4));
+    }
+
+    #[test]
+    fn explanations() {
+        assert_eq!(
+            explain("7pqrstsixteen", &Options::PART_2, false),
+            "7pqrstsixteen | first digit \"7\" @0..1 | last word \"six\" @6..9 | 76"
+        );
+        assert_eq!(
+            explain("7pqrstsixteen", &Options::PART_1, false),
+            "7pqrstsixteen | first digit \"7\" @0..1 | last digit \"7\" @0..1 | 77"
+        );
+        assert_eq!(explain("abc", &Options::PART_2, false), "abc | no digits");
+    }
+
+    #[test]
+    fn highlights() {
+        let (first, last) = first_and_last("a1b2c", &Options::PART_1).unwrap();
+        assert_eq!(
+            highlight("a1b2c", &first, &last),
+            "a\x1b[32m1\x1b[0mb\x1b[36m2\x1b[0mc"
+        );
+
+        // Overlapping words keep the shared letter in the first colour
+        let (first, last) = first_and_last("twone", &Options::PART_2).unwrap();
+        assert_eq!(
+            highlight("twone", &first, &last),
+            "\x1b[32mtwo\x1b[0m\x1b[36mne\x1b[0m"
+        );
+
+        // A lone token is only coloured once
+        let (first, last) = first_and_last("x5y", &Options::PART_1).unwrap();
+        assert_eq!(highlight("x5y", &first, &last), "x\x1b[32m5\x1b[0my");
+    }
+
+    #[test]
+    fn args() {
+        let args = ["--words", "compound", "--last", "token"].map(String::from);
+        let options = Options::PART_2.with_args(args).unwrap();
+        assert_eq!(options.words, Words::Compound);
+        assert_eq!(options.first, Part::Digit);
+        assert_eq!(options.last, Part::Token);
+        assert!(!options.unicode);
+        assert!(
+            Options::PART_1
+                .with_args(["--unicode".to_string()])
+                .unwrap()
+                .unicode
+        );
+
+        assert!(Options::PART_2.with_args(["--words".to_string()]).is_err());
+        assert!(Options::PART_2.with_args(["--bogus".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,154 @@
+//! Streaming fast path for part 1.
+//!
+//! Reads calibration lines straight out of a `BufRead` buffer without collecting them into
+//! `String`s, finds the first and last ASCII digit eight bytes at a time and does the arithmetic
+//! directly, so the whole file never has to be in memory.
+
+use std::io::{self, BufRead};
+
+/// `0x0101...01`, one in every byte lane.
+const LANES: u64 = u64::MAX / 255;
+/// The high bit of every byte lane.
+const HIGH_BITS: u64 = LANES * 0x80;
+
+/// Returns a mask with the high bit set in every byte lane of `word` holding an ASCII digit.
+///
+/// This is the "count bytes between m and n" trick from Bit Twiddling Hacks. Each lane is tested
+/// on its low seven bits (so no carries cross lanes) and bytes with the high bit set are masked
+/// out at the end.
+fn digit_mask(word: u64) -> u64 {
+    let low = word & (LANES * 0x7f);
+    let at_most_nine = LANES * (0x7f + b'9' as u64 + 1) - low;
+    let at_least_zero = low + LANES * (0x7f - (b'0' as u64 - 1));
+    at_most_nine & at_least_zero & !word & HIGH_BITS
+}
+
+/// Returns the first ASCII digit in `bytes`.
+fn first_digit(bytes: &[u8]) -> Option<u8> {
+    let mut chunks = bytes.chunks_exact(8);
+    for chunk in &mut chunks {
+        let mask = digit_mask(u64::from_le_bytes(chunk.try_into().unwrap()));
+        if mask != 0 {
+            return Some(chunk[mask.trailing_zeros() as usize / 8]);
+        }
+    }
+    chunks.remainder().iter().copied().find(u8::is_ascii_digit)
+}
+
+/// Returns the last ASCII digit in `bytes`.
+fn last_digit(bytes: &[u8]) -> Option<u8> {
+    let mut chunks = bytes.rchunks_exact(8);
+    for chunk in &mut chunks {
+        let mask = digit_mask(u64::from_le_bytes(chunk.try_into().unwrap()));
+        if mask != 0 {
+            return Some(chunk[7 - mask.leading_zeros() as usize / 8]);
+        }
+    }
+    chunks.remainder().iter().copied().rfind(u8::is_ascii_digit)
+}
+
+/// Returns the calibration value of a single line of bytes, `None` if it has no digits.
+///
+/// # Examples
+///
+/// - `b"abc1sd3"` -> `Some(13)`
+/// - `b"fhcg5s"` -> `Some(55)`
+pub fn line_value(line: &[u8]) -> Option<u64> {
+    let first = first_digit(line)?;
+    let last = last_digit(line)?;
+    Some(((first - b'0') * 10 + (last - b'0')) as u64)
+}
+
+/// Adds the value of `line` to `sum`, failing on lines without digits.
+fn add_line(sum: &mut u64, line: &[u8], number: &mut usize) -> io::Result<()> {
+    *number += 1;
+    match line_value(line) {
+        Some(value) => {
+            *sum += value;
+            Ok(())
+        }
+        None => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Line {number} must contain at least one digit."),
+        )),
+    }
+}
+
+/// Returns the sum of the part 1 calibration values of every line read from `reader`.
+///
+/// Lines are scanned in place inside the reader's buffer; only a line straddling two buffer
+/// fills is copied.
+pub fn sum_calibration(mut reader: impl BufRead) -> io::Result<u64> {
+    let mut sum = 0;
+    let mut number = 0;
+    let mut carry: Vec<u8> = vec![];
+
+    loop {
+        let buf = reader.fill_buf()?;
+        if buf.is_empty() {
+            break;
+        }
+
+        let mut start = 0;
+        for end in memchr::memchr_iter(b'\n', buf) {
+            if carry.is_empty() {
+                add_line(&mut sum, &buf[start..end], &mut number)?;
+            } else {
+                carry.extend_from_slice(&buf[start..end]);
+                add_line(&mut sum, &carry, &mut number)?;
+                carry.clear();
+            }
+            start = end + 1;
+        }
+        carry.extend_from_slice(&buf[start..]);
+
+        let len = buf.len();
+        reader.consume(len);
+    }
+
+    // Last line without a trailing newline
+    if !carry.is_empty() {
+        add_line(&mut sum, &carry, &mut number)?;
+    }
+
+    Ok(sum)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn digit_masks() {
+        assert_eq!(digit_mask(u64::from_le_bytes(*b"abcdefgh")), 0);
+        assert_eq!(
+            digit_mask(u64::from_le_bytes(*b"/:09\xb0\xb9 5")),
+            0x8000_0000_8080_0000
+        );
+    }
+
+    #[test]
+    fn line_values() {
+        assert_eq!(line_value(b"treb7uchet"), Some(77));
+        assert_eq!(line_value(b"a1b2c3d4e5f"), Some(15));
+        assert_eq!(line_value(b"abcdefgh1ijklmnopq2rstuvwxyz"), Some(12));
+        assert_eq!(line_value(b"9abcdefghijklmnop"), Some(99));
+        assert_eq!(line_value("\u{0669}٣\u{ff17}".as_bytes()), None);
+        assert_eq!(line_value(b"no digits here"), None);
+    }
+
+    #[test]
+    fn lines_across_buffer_fills() {
+        let input = "1abc2\npqr3stu8vwx\na1b2c3d4e5f\ntreb7uchet";
+        for capacity in 1..16 {
+            let reader = io::BufReader::with_capacity(capacity, input.as_bytes());
+            assert_eq!(sum_calibration(reader).unwrap(), 142);
+        }
+    }
+
+    #[test]
+    fn missing_digits() {
+        let err = sum_calibration("1abc2\nnope\n".as_bytes()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
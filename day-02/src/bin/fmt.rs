@@ -0,0 +1,65 @@
+//! Normalises day 2 game files into canonical `Game N: a red, b green; …` form.
+//!
+//! Usage: `fmt [-v] [--check] [FILE]...`
+//!
+//! With no files, reads stdin and writes the formatted games to stdout. Files are rewritten in
+//! place when they change. `--check` only lists the files that would change and exits non-zero if
+//! there are any.
+
+use std::io::Read;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let mut check = false;
+    let mut paths = vec![];
+    for arg in common::init_tracing(std::env::args().skip(1).collect()) {
+        match arg.as_str() {
+            "--check" => check = true,
+            _ => paths.push(arg),
+        }
+    }
+
+    if paths.is_empty() {
+        let mut input = String::new();
+        std::io::stdin()
+            .read_to_string(&mut input)
+            .expect("Failed to read stdin");
+        match day_02::format_games(&input) {
+            Ok(formatted) => print!("{formatted}"),
+            Err(e) => {
+                tracing::error!("<stdin>: {e}");
+                return ExitCode::FAILURE;
+            }
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    let mut failed = false;
+    for path in paths {
+        let input = std::fs::read_to_string(&path).expect("Failed to read input");
+        let formatted = match day_02::format_games(&input) {
+            Ok(a) => a,
+            Err(e) => {
+                tracing::error!("{path}: {e}");
+                failed = true;
+                continue;
+            }
+        };
+
+        if formatted == input {
+            continue;
+        }
+        if check {
+            println!("{path}");
+            failed = true;
+        } else {
+            tracing::info!("formatted {path}");
+            std::fs::write(&path, formatted).expect("Failed to write output");
+        }
+    }
+
+    match failed {
+        true => ExitCode::FAILURE,
+        false => ExitCode::SUCCESS,
+    }
+}
@@ -1,129 +1,119 @@
-use std::collections::HashMap;
+//! Solution for Advent of Code 2023, day 2, part 1.
+//!
+//! Usage: `part1 [-v] [--bag <colour=count,...>]... [--bags <file.toml>] [--input <file>]`
+//!
+//! Without any bags the puzzle bag (12 red, 13 green, 14 blue) is used and only the answer is
+//! printed. Given several bags, every game is checked against each of them and the ID sums are
+//! reported side by side.
+
+use day_02::{Bag, Game};
 use std::str::FromStr;
 
-#[derive(Debug, PartialEq)]
-struct Game {
-    gid: u32,
-    red: u32,
-    blue: u32,
-    green: u32,
+fn parse_games(input: &str) -> Vec<Game> {
+    day_02::parse_games(input).unwrap_or_else(|e| panic!("{e}"))
 }
 
-impl Game {
-    fn is_valid(&self, red: u32, blue: u32, green: u32) -> bool {
-        self.red <= red && self.blue <= blue && self.green <= green
+/// Returns the sum of the IDs of the games that could have been played with `bag`.
+fn sum_possible(games: &[Game], bag: &Bag) -> u32 {
+    let valid_games = games
+        .iter()
+        .filter(|g| match g.check_colours(bag) {
+            Ok(()) => true,
+            Err(e) => {
+                tracing::warn!("{e}");
+                false
+            }
+        })
+        .filter(|g| g.is_possible(bag))
+        .map(|a| a.id)
+        .collect::<Vec<u32>>();
+
+    for game in &valid_games {
+        tracing::debug!("valid game: {game}");
     }
+
+    valid_games.iter().sum()
+}
+
+fn calc_solution(input: &str, bag: &Bag) -> u32 {
+    sum_possible(&parse_games(input), bag)
+}
+
+/// Returns the ID sum for each bag, parsing the games only once.
+fn calc_solutions(input: &str, bags: &[(String, Bag)]) -> Vec<u32> {
+    let games = parse_games(input);
+    bags.iter()
+        .map(|(_, bag)| sum_possible(&games, bag))
+        .collect()
 }
 
-#[derive(Debug, PartialEq, Eq)]
-struct ParseGameError;
-
-impl FromStr for Game {
-    type Err = ParseGameError;
-
-    fn from_str(input: &str) -> Result<Self, Self::Err> {
-        // Parse the id
-        let num_end = input.find(':').unwrap();
-        let (first, last) = input.split_at(num_end);
-
-        let id = u32::from_str(first.strip_prefix("Game ").unwrap())
-            .ok()
-            .unwrap();
-
-        let mut max_colors: HashMap<String, u32> = HashMap::new();
-
-        let games = last.strip_prefix(":").unwrap().split(";");
-        for game in games {
-            let scrubbed = game.trim().replace(',', "");
-            let items = scrubbed.split_whitespace().map(String::from);
-
-            let numbers = items
-                .clone()
-                .step_by(2)
-                .map(|a| u32::from_str(a.as_str()).unwrap());
-            let colors = items.clone().skip(1).step_by(2);
-
-            for (color, number) in colors.zip(numbers) {
-                if max_colors.contains_key(&color) {
-                    // only add if bigger
-                    if number > *max_colors.get(&color).unwrap() {
-                        max_colors.insert(color, number);
-                    }
-                } else {
-                    max_colors.insert(color, number);
-                }
+fn main() {
+    let mut bags: Vec<(String, Bag)> = vec![];
+    let mut path = None;
+
+    let mut args = common::init_tracing(std::env::args().skip(1).collect()).into_iter();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .unwrap_or_else(|| panic!("Missing value for {arg}"))
+        };
+        match arg.as_str() {
+            "--bag" => {
+                let spec = value();
+                let bag = Bag::from_str(&spec).unwrap_or_else(|e| panic!("{e}"));
+                bags.push((spec, bag));
+            }
+            "--bags" => {
+                let file = std::fs::read_to_string(value()).expect("Failed to read bag file");
+                bags.extend(day_02::bags_from_toml(&file).unwrap_or_else(|e| panic!("{e}")));
             }
+            "--input" => path = Some(value()),
+            _ => panic!("Unknown argument: {arg}"),
         }
-        let alt: u32 = 0;
-
-        return Ok(Game {
-            gid: id,
-            blue: *max_colors.get("blue").unwrap_or(&alt),
-            red: *max_colors.get("red").unwrap_or(&alt),
-            green: *max_colors.get("green").unwrap_or(&alt),
-        });
     }
-}
 
-fn calc_solution(input: &str) -> u32 {
-    let valid_games = input
-        .split("\n")
-        .filter(|a| !a.is_empty())
-        .map(Game::from_str)
-        .map(Result::ok)
-        .map(Option::unwrap)
-        .filter(|g| g.is_valid(12, 14, 13))
-        .map(|a| a.gid);
-
-    for game in valid_games.clone() {
-        println!("valid game: {game}");
-    }
+    let input = match path {
+        Some(path) => std::fs::read_to_string(path).expect("Failed to read input"),
+        None => include_str!("./input.txt").to_string(),
+    };
 
-    valid_games.sum()
-}
+    if bags.len() <= 1 {
+        let bag = bags.pop().map_or_else(Bag::puzzle, |(_, bag)| bag);
+        println!("{}", calc_solution(&input, &bag));
+        return;
+    }
 
-fn main() {
-    let input = include_str!("./input.txt");
-    println!("{}", calc_solution(input));
+    let sums = calc_solutions(&input, &bags);
+    let width = bags.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+    println!("{:width$}  sum", "bag");
+    for ((name, _), sum) in bags.iter().zip(sums) {
+        println!("{name:width$}  {sum}");
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn game_from_str() {
-        let actual = Game::from_str("Game 11: 12 blue, 4 red; 10 red, 13 green, 6 blue; 2 green");
-        let expected = Game {
-            gid: 11,
-            red: 10,
-            blue: 12,
-            green: 13,
-        };
-        assert!(actual.is_ok());
-        assert_eq!(actual.unwrap(), expected);
-    }
+    const SAMPLE: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
 
     #[test]
-    fn other_game_from_str() {
-        let actual = Game::from_str("Game 79: 4 red, 3 green; 3 blue, 10 green, 4 red; 1 red, 12 green, 7 blue; 5 blue, 3 green, 6 red; 10 green, 1 blue, 5 red; 5 green, 5 red");
-        let expected = Game {
-            gid: 79,
-            red: 6,
-            blue: 7,
-            green: 12,
-        };
-        assert!(actual.is_ok());
-        assert_eq!(actual.unwrap(), expected);
+    fn sample_solution() {
+        assert_eq!(calc_solution(SAMPLE, &Bag::puzzle()), 8);
     }
 
     #[test]
-    fn sample_solution() {
-        let input = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
-Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
-Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
-Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
-Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
-        assert_eq!(calc_solution(input), 8);
+    fn batch_solutions() {
+        let bags = [
+            "red=12,green=13,blue=14",
+            "red=20,green=13,blue=15",
+            "red=4,green=3,blue=6",
+        ]
+        .map(|spec| (spec.to_string(), Bag::from_str(spec).unwrap()));
+        assert_eq!(calc_solutions(SAMPLE, &bags), vec![8, 15, 3]);
     }
 }
@@ -0,0 +1,44 @@
+//! Statistics report for a day 2 game file.
+//!
+//! Usage: `report [--json] [--top <n>] [--bag <colour=count,...>] [--input <file>]`
+//!
+//! Covers the distribution of each colour's minimum bag, the games the bag rules out and the
+//! round and colour responsible, the highest power games and the cubes drawn per colour.
+
+use day_02::report::Report;
+use day_02::Bag;
+use std::str::FromStr;
+
+fn main() {
+    let mut json = false;
+    let mut top = 5;
+    let mut bag = Bag::puzzle();
+    let mut path = None;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .unwrap_or_else(|| panic!("Missing value for {arg}"))
+        };
+        match arg.as_str() {
+            "--json" => json = true,
+            "--top" => top = value().parse().expect("Expected a number of games"),
+            "--bag" => bag = Bag::from_str(&value()).unwrap_or_else(|e| panic!("{e}")),
+            "--input" => path = Some(value()),
+            _ => panic!("Unknown argument: {arg}"),
+        }
+    }
+
+    let input = match path {
+        Some(path) => std::fs::read_to_string(path).expect("Failed to read input"),
+        None => include_str!("./input.txt").to_string(),
+    };
+    let games = day_02::parse_games(&input).unwrap_or_else(|e| panic!("{e}"));
+    let report = Report::new(&games, &bag, top);
+
+    match json {
+        true => println!("{:#}", report.to_json()),
+        false => print!("{}", report.to_table()),
+    }
+}
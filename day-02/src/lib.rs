@@ -0,0 +1,958 @@
+//! Shared game model for Advent of Code 2023, day 2.
+//!
+//! A game keeps every round it was played with, and all the puzzle questions (which bag could
+//! have been used, how many cubes were needed) are derived from those rounds. Colours are not
+//! limited to red, green and blue; the puzzle's bag is just one configuration.
+
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+use std::sync::{Mutex, OnceLock};
+
+pub mod report;
+
+/// An interned colour name. Colours compare and hash by id, the name lives in a process wide
+/// registry.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Colour(u32);
+
+/// Names of every colour seen so far, indexed by id. The puzzle colours are seeded so they can be
+/// constants.
+fn registry() -> &'static Mutex<Vec<&'static str>> {
+    static REGISTRY: OnceLock<Mutex<Vec<&'static str>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| Mutex::new(vec!["red", "green", "blue"]))
+}
+
+impl Colour {
+    pub const RED: Colour = Colour(0);
+    pub const GREEN: Colour = Colour(1);
+    pub const BLUE: Colour = Colour(2);
+
+    /// Returns the colour called `name`, registering it on first use.
+    pub fn intern(name: &str) -> Colour {
+        let mut names = registry().lock().unwrap();
+        match names.iter().position(|n| *n == name) {
+            Some(id) => Colour(id as u32),
+            None => {
+                // Colour names are few and live for the whole run, leaking them is fine
+                names.push(Box::leak(name.into()));
+                Colour(names.len() as u32 - 1)
+            }
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        registry().lock().unwrap()[self.0 as usize]
+    }
+}
+
+impl fmt::Display for Colour {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl fmt::Debug for Colour {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Colour({})", self.name())
+    }
+}
+
+/// The cubes drawn in a single round of a game, in the order they were listed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Round {
+    draws: Vec<(Colour, u32)>,
+}
+
+impl Round {
+    pub fn new() -> Round {
+        Round { draws: vec![] }
+    }
+
+    /// Adds `count` cubes of `colour`, on top of any already drawn.
+    pub fn add(&mut self, colour: Colour, count: u32) {
+        match self.draws.iter_mut().find(|(c, _)| *c == colour) {
+            Some((_, n)) => *n += count,
+            None => self.draws.push((colour, count)),
+        }
+    }
+
+    /// Returns the number of `colour` cubes drawn, 0 if the colour wasn't drawn.
+    pub fn get(&self, colour: Colour) -> u32 {
+        self.draws
+            .iter()
+            .find(|(c, _)| *c == colour)
+            .map_or(0, |(_, n)| *n)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Colour, u32)> + '_ {
+        self.draws.iter().copied()
+    }
+
+    /// Returns the total number of cubes drawn across all colours.
+    pub fn total(&self) -> u32 {
+        self.draws.iter().map(|(_, n)| n).sum()
+    }
+}
+
+impl fmt::Display for Round {
+    /// Writes the canonical `a red, b green` form.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, (colour, count)) in self.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{count} {colour}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromIterator<(Colour, u32)> for Round {
+    fn from_iter<T: IntoIterator<Item = (Colour, u32)>>(iter: T) -> Self {
+        let mut round = Round::new();
+        for (colour, count) in iter {
+            round.add(colour, count);
+        }
+        round
+    }
+}
+
+/// The cubes loaded into the bag before a game. A bag lists every colour it holds, so drawing
+/// any other colour is an error rather than a silent zero.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Bag {
+    counts: Vec<(Colour, u32)>,
+}
+
+impl Bag {
+    /// Returns the bag from the puzzle: 12 red, 13 green and 14 blue cubes.
+    pub fn puzzle() -> Bag {
+        Bag::from_iter([(Colour::RED, 12), (Colour::GREEN, 13), (Colour::BLUE, 14)])
+    }
+
+    /// Returns the number of `colour` cubes, `None` if the bag doesn't hold that colour.
+    pub fn get(&self, colour: Colour) -> Option<u32> {
+        self.counts
+            .iter()
+            .find(|(c, _)| *c == colour)
+            .map(|(_, n)| *n)
+    }
+
+    pub fn contains(&self, colour: Colour) -> bool {
+        self.get(colour).is_some()
+    }
+
+    pub fn colours(&self) -> impl Iterator<Item = Colour> + '_ {
+        self.counts.iter().map(|(c, _)| *c)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Colour, u32)> + '_ {
+        self.counts.iter().copied()
+    }
+
+    /// Returns true if `round` could have been drawn from this bag.
+    pub fn holds(&self, round: &Round) -> bool {
+        round
+            .iter()
+            .all(|(colour, n)| n <= self.get(colour).unwrap_or(0))
+    }
+
+    /// Returns the first colour of `round` drawn more times than the bag holds, with the number
+    /// drawn.
+    pub fn first_overdrawn(&self, round: &Round) -> Option<(Colour, u32)> {
+        round
+            .iter()
+            .find(|(colour, n)| *n > self.get(*colour).unwrap_or(0))
+    }
+
+    /// Returns the product of the cube counts of every colour in the bag, saturating at
+    /// `u64::MAX`.
+    pub fn power(&self) -> u64 {
+        saturating_product(self.counts.iter().map(|(_, n)| *n))
+    }
+}
+
+/// Multiplies cube counts together without overflowing: with enough colours even small counts
+/// pass `u32::MAX`, and past a dozen or so large ones `u64::MAX`.
+fn saturating_product(counts: impl Iterator<Item = u32>) -> u64 {
+    counts.fold(1, |product, n| product.saturating_mul(u64::from(n)))
+}
+
+impl FromIterator<(Colour, u32)> for Bag {
+    /// Builds a bag, keeping the last count given for a repeated colour.
+    fn from_iter<T: IntoIterator<Item = (Colour, u32)>>(iter: T) -> Self {
+        let mut bag = Bag::default();
+        for (colour, count) in iter {
+            match bag.counts.iter_mut().find(|(c, _)| *c == colour) {
+                Some((_, n)) => *n = count,
+                None => bag.counts.push((colour, count)),
+            }
+        }
+        bag
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseBagError {
+    /// An entry wasn't of the form `colour=count`.
+    BadEntry(String),
+    /// A count wasn't a whole number of cubes.
+    BadCount(String),
+    /// The bag file isn't TOML, or a bag in it isn't a table of counts.
+    BadFile(String),
+}
+
+impl fmt::Display for ParseBagError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseBagError::BadEntry(e) => write!(f, "expected colour=count, got {e:?}"),
+            ParseBagError::BadCount(e) => write!(f, "expected a cube count, got {e:?}"),
+            ParseBagError::BadFile(e) => write!(f, "invalid bag file: {e}"),
+        }
+    }
+}
+
+impl FromStr for Bag {
+    type Err = ParseBagError;
+
+    /// Parses a bag spec such as `red=12,green=13,blue=14`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .map(|entry| {
+                let (colour, count) = entry
+                    .split_once('=')
+                    .ok_or_else(|| ParseBagError::BadEntry(entry.to_string()))?;
+                let count = count
+                    .trim()
+                    .parse()
+                    .map_err(|_| ParseBagError::BadCount(count.to_string()))?;
+                match colour.trim() {
+                    "" => Err(ParseBagError::BadEntry(entry.to_string())),
+                    colour => Ok((Colour::intern(colour), count)),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Reads named bags from a TOML document, one table per bag in file order:
+///
+/// ```toml
+/// [puzzle]
+/// red = 12
+/// green = 13
+/// blue = 14
+/// ```
+pub fn bags_from_toml(input: &str) -> Result<Vec<(String, Bag)>, ParseBagError> {
+    let table: toml::Table = input
+        .parse()
+        .map_err(|e: toml::de::Error| ParseBagError::BadFile(e.message().to_string()))?;
+
+    table
+        .iter()
+        .map(|(name, counts)| {
+            let counts = counts
+                .as_table()
+                .ok_or_else(|| ParseBagError::BadFile(format!("{name} is not a table")))?;
+            let bag = counts
+                .iter()
+                .map(|(colour, count)| {
+                    count
+                        .as_integer()
+                        .and_then(|n| u32::try_from(n).ok())
+                        .map(|n| (Colour::intern(colour), n))
+                        .ok_or_else(|| ParseBagError::BadCount(count.to_string()))
+                })
+                .collect::<Result<Bag, _>>()?;
+            Ok((name.clone(), bag))
+        })
+        .collect()
+}
+
+/// A game drew a colour the bag it was checked against doesn't hold.
+#[derive(Debug, PartialEq, Eq)]
+pub struct UnknownColourError {
+    pub game: u32,
+    pub round: usize,
+    pub colour: Colour,
+}
+
+impl fmt::Display for UnknownColourError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "game {} draws {} in round {}, which the bag doesn't hold",
+            self.game,
+            self.colour,
+            self.round + 1
+        )
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Game {
+    pub id: u32,
+    pub rounds: Vec<Round>,
+}
+
+impl Game {
+    /// Returns the smallest bag that could have played every round, the per colour maximum.
+    pub fn min_bag(&self) -> Bag {
+        let mut max: Round = Round::new();
+        for (colour, count) in self.rounds.iter().flat_map(Round::iter) {
+            if count > max.get(colour) {
+                max.add(colour, count - max.get(colour));
+            }
+        }
+        max.iter().collect()
+    }
+
+    /// Returns an error for the first draw of a colour `bag` doesn't hold.
+    pub fn check_colours(&self, bag: &Bag) -> Result<(), UnknownColourError> {
+        for (round, draws) in self.rounds.iter().enumerate() {
+            if let Some((colour, _)) = draws.iter().find(|(c, _)| !bag.contains(*c)) {
+                return Err(UnknownColourError {
+                    game: self.id,
+                    round,
+                    colour,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns true if every round could have been drawn from `bag`. Colours missing from the bag
+    /// count as zero cubes.
+    pub fn is_possible(&self, bag: &Bag) -> bool {
+        self.first_impossible_round(bag).is_none()
+    }
+
+    /// Returns the index of the first round needing more cubes than `bag` holds.
+    pub fn first_impossible_round(&self, bag: &Bag) -> Option<usize> {
+        self.rounds.iter().position(|round| !bag.holds(round))
+    }
+
+    /// Returns the number of cubes drawn in each round.
+    pub fn round_totals(&self) -> Vec<u32> {
+        self.rounds.iter().map(Round::total).collect()
+    }
+
+    pub fn is_valid(&self, red: u32, blue: u32, green: u32) -> bool {
+        self.is_possible(&Bag::from_iter([
+            (Colour::RED, red),
+            (Colour::BLUE, blue),
+            (Colour::GREEN, green),
+        ]))
+    }
+
+    /// Returns the product of the minimum cube counts of every colour `bag` holds, saturating at
+    /// `u64::MAX`. Colours the game never drew count as zero.
+    pub fn power_for(&self, bag: &Bag) -> u64 {
+        let min = self.min_bag();
+        saturating_product(bag.colours().map(|c| min.get(c).unwrap_or(0)))
+    }
+
+    pub fn calc_power(&self) -> u64 {
+        self.power_for(&Bag::puzzle())
+    }
+}
+
+impl fmt::Display for Game {
+    /// Writes the canonical `Game N: a red, b green; c blue` form, which parses back to `self`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Game {}:", self.id)?;
+        for (i, round) in self.rounds.iter().enumerate() {
+            let separator = if i == 0 { "" } else { ";" };
+            write!(f, "{separator} {round}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Why a game record didn't match `Game <id>: <n> <colour>(, <n> <colour>)*(; …)*`. Every
+/// variant carries the byte span of the offending text within the line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseGameError {
+    /// The line doesn't start with `Game`.
+    MissingPrefix { span: Range<usize> },
+    /// No game number after `Game`.
+    MissingId { span: Range<usize> },
+    /// A game number too large to hold.
+    BadId { span: Range<usize> },
+    /// No `:` after the game number.
+    MissingColon { span: Range<usize> },
+    /// A cube count was expected, e.g. in an empty round.
+    MissingCount { span: Range<usize> },
+    /// A cube count too large to hold.
+    BadCount { span: Range<usize> },
+    /// A count without a colour, such as the `5` in `4 red 5`.
+    OddTokenCount { span: Range<usize> },
+    /// A colour drawn twice in the same round.
+    RepeatedColour {
+        colour: Colour,
+        span: Range<usize>,
+        first: Range<usize>,
+    },
+    /// Text after a complete draw that isn't `,` or `;`.
+    TrailingGarbage { span: Range<usize> },
+}
+
+impl ParseGameError {
+    pub fn span(&self) -> Range<usize> {
+        match self {
+            ParseGameError::MissingPrefix { span }
+            | ParseGameError::MissingId { span }
+            | ParseGameError::BadId { span }
+            | ParseGameError::MissingColon { span }
+            | ParseGameError::MissingCount { span }
+            | ParseGameError::BadCount { span }
+            | ParseGameError::OddTokenCount { span }
+            | ParseGameError::RepeatedColour { span, .. }
+            | ParseGameError::TrailingGarbage { span } => span.clone(),
+        }
+    }
+
+    /// Returns `line` with the error's span underlined, for error messages.
+    pub fn render(&self, line: &str) -> String {
+        let span = self.span();
+        let indent = line[..span.start].chars().count();
+        let width = line[span].chars().count().max(1);
+        format!("{line}\n{}{}", " ".repeat(indent), "^".repeat(width))
+    }
+}
+
+impl fmt::Display for ParseGameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let span = self.span();
+        match self {
+            ParseGameError::MissingPrefix { .. } => write!(f, "expected `Game`")?,
+            ParseGameError::MissingId { .. } => write!(f, "expected a game number")?,
+            ParseGameError::BadId { .. } => write!(f, "game number is too large")?,
+            ParseGameError::MissingColon { .. } => write!(f, "expected `:`")?,
+            ParseGameError::MissingCount { .. } => write!(f, "expected a cube count")?,
+            ParseGameError::BadCount { .. } => write!(f, "cube count is too large")?,
+            ParseGameError::OddTokenCount { .. } => write!(f, "cube count has no colour")?,
+            ParseGameError::RepeatedColour { colour, first, .. } => write!(
+                f,
+                "{colour} already drawn this round at {}..{}",
+                first.start, first.end
+            )?,
+            ParseGameError::TrailingGarbage { .. } => write!(f, "expected `,`, `;` or the end")?,
+        }
+        write!(f, " at {}..{}", span.start, span.end)
+    }
+}
+
+/// A line of a game file that failed to parse.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseInputError {
+    /// One based line number.
+    pub line: usize,
+    pub text: String,
+    pub error: ParseGameError,
+}
+
+impl fmt::Display for ParseInputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}: {}\n{}",
+            self.line,
+            self.error,
+            self.error.render(&self.text)
+        )
+    }
+}
+
+/// Parses every non-empty line of a game file.
+pub fn parse_games(input: &str) -> Result<Vec<Game>, ParseInputError> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(number, line)| {
+            Game::from_str(line).map_err(|error| ParseInputError {
+                line: number + 1,
+                text: line.to_string(),
+                error,
+            })
+        })
+        .collect()
+}
+
+/// Rewrites a game file in canonical form, one game per line with blank lines dropped.
+pub fn format_games(input: &str) -> Result<String, ParseInputError> {
+    Ok(parse_games(input)?
+        .iter()
+        .map(|game| format!("{game}\n"))
+        .collect())
+}
+
+/// Hand rolled scanner over a single game record.
+struct Cursor<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn peek(&self) -> Option<char> {
+        self.input[self.pos..].chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.take_while(char::is_whitespace);
+    }
+
+    /// Consumes `c` if it's next.
+    fn eat(&mut self, c: char) -> bool {
+        self.eat_str(c.encode_utf8(&mut [0; 4]))
+    }
+
+    /// Consumes `s` if it's next.
+    fn eat_str(&mut self, s: &str) -> bool {
+        let found = self.input[self.pos..].starts_with(s);
+        if found {
+            self.pos += s.len();
+        }
+        found
+    }
+
+    /// Consumes characters while `f` holds and returns their span.
+    fn take_while(&mut self, f: impl Fn(char) -> bool) -> Range<usize> {
+        let start = self.pos;
+        let len = self.input[start..]
+            .find(|c| !f(c))
+            .unwrap_or(self.input.len() - start);
+        self.pos += len;
+        start..self.pos
+    }
+
+    /// Returns the span of the next word without consuming it, for pointing at unexpected text.
+    fn next_word(&self) -> Range<usize> {
+        let rest = &self.input[self.pos..];
+        let len = rest
+            .find(|c: char| c.is_whitespace() || ",;:".contains(c))
+            .unwrap_or(rest.len());
+        // Always point at something, even a lone separator
+        let len = match (len, rest.chars().next()) {
+            (0, Some(c)) => c.len_utf8(),
+            _ => len,
+        };
+        self.pos..self.pos + len
+    }
+
+    /// Returns true if the next word is a number standing on its own at the end of a draw list.
+    fn at_dangling_count(&self) -> bool {
+        let rest = &self.input[self.pos..];
+        let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        let after = rest[digits..].trim_start();
+        digits > 0 && (after.is_empty() || after.starts_with([',', ';']))
+    }
+
+    /// Parses `<n> <colour>` with optional whitespace in between.
+    fn draw(&mut self) -> Result<(u32, Colour, Range<usize>), ParseGameError> {
+        self.skip_whitespace();
+        let count_span = self.take_while(|c| c.is_ascii_digit());
+        if count_span.is_empty() {
+            return Err(ParseGameError::MissingCount {
+                span: self.next_word(),
+            });
+        }
+        let count =
+            self.input[count_span.clone()]
+                .parse()
+                .map_err(|_| ParseGameError::BadCount {
+                    span: count_span.clone(),
+                })?;
+
+        self.skip_whitespace();
+        let colour_span = self.take_while(char::is_alphabetic);
+        if colour_span.is_empty() {
+            return Err(ParseGameError::OddTokenCount { span: count_span });
+        }
+        let colour = Colour::intern(&self.input[colour_span.clone()]);
+
+        Ok((count, colour, colour_span))
+    }
+}
+
+impl FromStr for Game {
+    type Err = ParseGameError;
+
+    /// Parses a record such as `Game 3: 8 green, 6 blue; 5 blue, 4 red`. Whitespace between
+    /// tokens is free form, everything else is strict.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut cursor = Cursor { input, pos: 0 };
+
+        cursor.skip_whitespace();
+        if !cursor.eat_str("Game") {
+            return Err(ParseGameError::MissingPrefix {
+                span: cursor.next_word(),
+            });
+        }
+
+        cursor.skip_whitespace();
+        let id_span = cursor.take_while(|c| c.is_ascii_digit());
+        if id_span.is_empty() {
+            return Err(ParseGameError::MissingId {
+                span: cursor.next_word(),
+            });
+        }
+        let id = input[id_span.clone()]
+            .parse()
+            .map_err(|_| ParseGameError::BadId { span: id_span })?;
+
+        cursor.skip_whitespace();
+        if !cursor.eat(':') {
+            return Err(ParseGameError::MissingColon {
+                span: cursor.next_word(),
+            });
+        }
+
+        let mut rounds = vec![];
+        let mut round = Round::new();
+        let mut seen: Vec<(Colour, Range<usize>)> = vec![];
+
+        loop {
+            let (count, colour, colour_span) = cursor.draw()?;
+            if let Some((_, first)) = seen.iter().find(|(c, _)| *c == colour) {
+                return Err(ParseGameError::RepeatedColour {
+                    colour,
+                    span: colour_span,
+                    first: first.clone(),
+                });
+            }
+            seen.push((colour, colour_span));
+            round.add(colour, count);
+
+            cursor.skip_whitespace();
+            if cursor.eat(',') {
+                continue;
+            }
+            if cursor.eat(';') {
+                rounds.push(std::mem::take(&mut round));
+                seen.clear();
+                continue;
+            }
+            if cursor.peek().is_none() {
+                rounds.push(round);
+                break;
+            }
+
+            return Err(match cursor.at_dangling_count() {
+                true => ParseGameError::OddTokenCount {
+                    span: cursor.take_while(|c| c.is_ascii_digit()),
+                },
+                false => ParseGameError::TrailingGarbage {
+                    span: cursor.pos..input.trim_end().len().max(cursor.pos),
+                },
+            });
+        }
+
+        Ok(Game { id, rounds })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn round(draws: &[(&str, u32)]) -> Round {
+        draws.iter().map(|(c, n)| (Colour::intern(c), *n)).collect()
+    }
+
+    fn bag(counts: &[(&str, u32)]) -> Bag {
+        counts
+            .iter()
+            .map(|(c, n)| (Colour::intern(c), *n))
+            .collect()
+    }
+
+    #[test]
+    fn interning() {
+        assert_eq!(Colour::intern("red"), Colour::RED);
+        assert_eq!(Colour::intern("blue"), Colour::BLUE);
+        assert_eq!(Colour::intern("mauve"), Colour::intern("mauve"));
+        assert_ne!(Colour::intern("mauve"), Colour::intern("taupe"));
+        assert_eq!(Colour::intern("taupe").name(), "taupe");
+        assert_eq!(Colour::GREEN.to_string(), "green");
+    }
+
+    #[test]
+    fn game_from_str() {
+        let actual = Game::from_str("Game 11: 12 blue, 4 red; 10 red, 13 green, 6 blue; 2 green");
+        let expected = Game {
+            id: 11,
+            rounds: vec![
+                round(&[("blue", 12), ("red", 4)]),
+                round(&[("red", 10), ("green", 13), ("blue", 6)]),
+                round(&[("green", 2)]),
+            ],
+        };
+        assert_eq!(actual, Ok(expected));
+    }
+
+    #[test]
+    fn other_game_from_str() {
+        let game = Game::from_str("Game 79: 4 red, 3 green; 3 blue, 10 green, 4 red; 1 red, 12 green, 7 blue; 5 blue, 3 green, 6 red; 10 green, 1 blue, 5 red; 5 green, 5 red").unwrap();
+        assert_eq!(game.id, 79);
+        assert_eq!(game.rounds.len(), 6);
+        assert_eq!(
+            game.min_bag(),
+            bag(&[("red", 6), ("green", 12), ("blue", 7)])
+        );
+    }
+
+    #[test]
+    fn derived_queries() {
+        let game = Game::from_str(
+            "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red",
+        )
+        .unwrap();
+
+        assert!(!game.is_possible(&Bag::puzzle()));
+        assert_eq!(game.first_impossible_round(&Bag::puzzle()), Some(0));
+        assert_eq!(game.round_totals(), vec![34, 22, 6]);
+        assert!(game.is_possible(&game.min_bag()));
+        assert!(game.is_valid(20, 6, 13));
+        assert!(!game.is_valid(19, 6, 13));
+        assert_eq!(game.calc_power(), 20 * 6 * 13);
+        assert_eq!(game.min_bag().power(), 20 * 6 * 13);
+
+        let later = Game::from_str("Game 4: 1 green; 3 green, 15 blue").unwrap();
+        assert_eq!(later.first_impossible_round(&Bag::puzzle()), Some(1));
+    }
+
+    #[test]
+    fn missing_colours_have_no_power() {
+        let game = Game::from_str("Game 1: 3 blue, 4 red; 1 red, 6 blue").unwrap();
+        assert_eq!(game.calc_power(), 0);
+    }
+
+    #[test]
+    fn power_saturates() {
+        let huge = bag(&[("red", u32::MAX), ("green", u32::MAX), ("blue", u32::MAX)]);
+        assert_eq!(huge.power(), u64::MAX);
+
+        let game = Game::from_str("Game 1: 100000 red, 100000 green, 100000 blue").unwrap();
+        assert_eq!(game.calc_power(), 1_000_000_000_000_000);
+        // Saturating first still gives zero when a count is zero
+        let empty = bag(&[("red", u32::MAX), ("green", u32::MAX), ("blue", 0)]);
+        assert_eq!(empty.power(), 0);
+    }
+
+    #[test]
+    fn bag_specs() {
+        assert_eq!(Bag::from_str("red=12,green=13,blue=14"), Ok(Bag::puzzle()));
+        assert_eq!(
+            Bag::from_str("blue = 14, red=12"),
+            Ok(bag(&[("blue", 14), ("red", 12)]))
+        );
+        assert_eq!(
+            Bag::from_str("red=12,green"),
+            Err(ParseBagError::BadEntry("green".to_string()))
+        );
+        assert_eq!(
+            Bag::from_str("red=-1"),
+            Err(ParseBagError::BadCount("-1".to_string()))
+        );
+        assert_eq!(
+            Bag::from_str("=4"),
+            Err(ParseBagError::BadEntry("=4".to_string()))
+        );
+    }
+
+    #[test]
+    fn bag_files() {
+        let bags = bags_from_toml(
+            "[puzzle]
+red = 12
+green = 13
+blue = 14
+
+[tiny]
+red = 1
+",
+        )
+        .unwrap();
+        assert_eq!(
+            bags,
+            vec![
+                ("puzzle".to_string(), Bag::puzzle()),
+                ("tiny".to_string(), bag(&[("red", 1)])),
+            ]
+        );
+
+        assert!(matches!(
+            bags_from_toml("red = 12"),
+            Err(ParseBagError::BadFile(_))
+        ));
+        assert!(matches!(
+            bags_from_toml("[a]\nred = \"lots\""),
+            Err(ParseBagError::BadCount(_))
+        ));
+        assert!(matches!(
+            bags_from_toml("[a\nred = 1"),
+            Err(ParseBagError::BadFile(_))
+        ));
+    }
+
+    fn parse_err(input: &str) -> ParseGameError {
+        Game::from_str(input).unwrap_err()
+    }
+
+    #[test]
+    fn whitespace_is_free_form() {
+        let tidy = Game::from_str("Game 5: 6 red, 1 blue; 2 green").unwrap();
+        let messy = Game::from_str("  Game  5 :6 red ,1   blue;2\tgreen  ").unwrap();
+        assert_eq!(tidy, messy);
+    }
+
+    #[test]
+    fn grammar_errors() {
+        assert_eq!(
+            parse_err("Gme 1: 3 red"),
+            ParseGameError::MissingPrefix { span: 0..3 }
+        );
+        assert_eq!(
+            parse_err("Game : 3 red"),
+            ParseGameError::MissingId { span: 5..6 }
+        );
+        assert_eq!(
+            parse_err("Game x: 3 red"),
+            ParseGameError::MissingId { span: 5..6 }
+        );
+        assert_eq!(
+            parse_err("Game 99999999999: 1 red"),
+            ParseGameError::BadId { span: 5..16 }
+        );
+        assert_eq!(
+            parse_err("Game 1 3 red"),
+            ParseGameError::MissingColon { span: 7..8 }
+        );
+        assert_eq!(
+            parse_err("Game 3: 4 red 5"),
+            ParseGameError::OddTokenCount { span: 14..15 }
+        );
+        assert_eq!(
+            parse_err("Game 3: 4 red, 5; 1 blue"),
+            ParseGameError::OddTokenCount { span: 15..16 }
+        );
+        assert_eq!(
+            parse_err("Game 3: 4 red, 5 blue, 2 red"),
+            ParseGameError::RepeatedColour {
+                colour: Colour::RED,
+                span: 25..28,
+                first: 10..13,
+            }
+        );
+        assert_eq!(
+            parse_err("Game 3: 4 red; 5 blue!!"),
+            ParseGameError::TrailingGarbage { span: 21..23 }
+        );
+        assert_eq!(
+            parse_err("Game 3: 4 red 5 blue"),
+            ParseGameError::TrailingGarbage { span: 14..20 }
+        );
+        assert_eq!(
+            parse_err("Game 3: 4 red;; 5 blue"),
+            ParseGameError::MissingCount { span: 14..15 }
+        );
+        assert_eq!(
+            parse_err("Game 3: 4 red;"),
+            ParseGameError::MissingCount { span: 14..14 }
+        );
+        assert_eq!(
+            parse_err("Game 3: 99999999999 red"),
+            ParseGameError::BadCount { span: 8..19 }
+        );
+    }
+
+    #[test]
+    fn repeated_colours_across_rounds_are_fine() {
+        let game = Game::from_str("Game 2: 4 red; 2 red").unwrap();
+        assert_eq!(
+            game.rounds,
+            vec![round(&[("red", 4)]), round(&[("red", 2)])]
+        );
+    }
+
+    #[test]
+    fn error_messages() {
+        let err = parse_games("Game 1: 3 red\nGame 2: 4 red 5\n").unwrap_err();
+        assert_eq!(err.line, 2);
+        assert_eq!(
+            err.to_string(),
+            "line 2: cube count has no colour at 14..15\nGame 2: 4 red 5\n              ^"
+        );
+
+        let err = parse_games("Game 99999999999: 1 red").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1: game number is too large at 5..16\nGame 99999999999: 1 red\n     ^^^^^^^^^^^"
+        );
+    }
+
+    #[test]
+    fn display() {
+        let line = "Game 11: 12 blue, 4 red; 10 red, 13 green, 6 blue; 2 green";
+        assert_eq!(Game::from_str(line).unwrap().to_string(), line);
+    }
+
+    #[test]
+    fn formatting() {
+        let messy = "Game 1:3 blue ,4 red;1 red,2 green ,  6 blue\n\n  Game 2 : 1 blue;2 green  \n";
+        assert_eq!(
+            format_games(messy),
+            Ok(
+                "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue\nGame 2: 1 blue; 2 green\n"
+                    .to_string()
+            )
+        );
+        assert_eq!(format_games("Game 1: 3 blue red").unwrap_err().line, 1);
+    }
+
+    /// A round of one to four distinct colours.
+    fn round_strategy() -> impl Strategy<Value = Round> {
+        prop::collection::btree_map("[a-z]{1,8}", any::<u32>(), 1..5)
+            .prop_map(|draws| draws.iter().map(|(c, n)| (Colour::intern(c), *n)).collect())
+    }
+
+    fn game_strategy() -> impl Strategy<Value = Game> {
+        (any::<u32>(), prop::collection::vec(round_strategy(), 1..8))
+            .prop_map(|(id, rounds)| Game { id, rounds })
+    }
+
+    proptest! {
+        #[test]
+        fn display_round_trips(game in game_strategy()) {
+            prop_assert_eq!(Game::from_str(&game.to_string()), Ok(game));
+        }
+    }
+
+    #[test]
+    fn arbitrary_colours() {
+        let game = Game::from_str("Game 7: 3 purple, 2 red; 1 orange; 4 purple").unwrap();
+        let rainbow = bag(&[("red", 2), ("purple", 4), ("orange", 1)]);
+
+        assert_eq!(game.check_colours(&rainbow), Ok(()));
+        assert!(game.is_possible(&rainbow));
+        assert_eq!(
+            game.min_bag(),
+            bag(&[("purple", 4), ("red", 2), ("orange", 1)])
+        );
+        assert_eq!(game.min_bag().power(), 8);
+
+        assert_eq!(
+            game.check_colours(&Bag::puzzle()),
+            Err(UnknownColourError {
+                game: 7,
+                round: 0,
+                colour: Colour::intern("purple"),
+            })
+        );
+        assert!(!game.is_possible(&Bag::puzzle()));
+    }
+}
@@ -0,0 +1,291 @@
+//! Statistics over a whole game file, mainly for sanity checking generated inputs.
+
+use crate::{Bag, Colour, Game};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Why a game couldn't have been played with the bag.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Impossible {
+    pub game: u32,
+    /// Zero based index of the first round that overdrew the bag.
+    pub round: usize,
+    pub colour: Colour,
+    pub drawn: u32,
+    /// Cubes of `colour` in the bag, 0 if the bag doesn't hold it.
+    pub available: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub games: usize,
+    /// For each colour in first seen order, how many games needed each minimum count. Games that
+    /// never drew a colour count as needing 0 of it.
+    pub min_bags: Vec<(Colour, BTreeMap<u32, usize>)>,
+    pub impossible: Vec<Impossible>,
+    /// `(game, power)` of the highest power games, highest first.
+    pub top_power: Vec<(u32, u64)>,
+    /// Cubes drawn per colour over every round of every game.
+    pub totals: Vec<(Colour, u64)>,
+}
+
+/// Returns the entry for `colour`, adding a default one at the end if it's new.
+fn entry<T: Default>(list: &mut Vec<(Colour, T)>, colour: Colour) -> &mut T {
+    let index = match list.iter().position(|(c, _)| *c == colour) {
+        Some(a) => a,
+        None => {
+            list.push((colour, T::default()));
+            list.len() - 1
+        }
+    };
+    &mut list[index].1
+}
+
+impl Report {
+    /// Builds a report of `games` checked against `bag`, keeping the `top` highest power games.
+    /// Power is taken over the colours `bag` holds.
+    pub fn new(games: &[Game], bag: &Bag, top: usize) -> Report {
+        let mut min_bags: Vec<(Colour, BTreeMap<u32, usize>)> = vec![];
+        let mut totals: Vec<(Colour, u64)> = vec![];
+        let mut impossible = vec![];
+
+        // Every colour has to be known before a game can be counted as not drawing it
+        let game_min_bags: Vec<Bag> = games.iter().map(Game::min_bag).collect();
+        for colour in game_min_bags.iter().flat_map(Bag::colours) {
+            entry(&mut min_bags, colour);
+        }
+
+        for (game, min_bag) in games.iter().zip(&game_min_bags) {
+            for (colour, counts) in &mut min_bags {
+                *counts.entry(min_bag.get(*colour).unwrap_or(0)).or_default() += 1;
+            }
+
+            for (colour, count) in game.rounds.iter().flat_map(|r| r.iter()) {
+                *entry(&mut totals, colour) += count as u64;
+            }
+
+            if let Some(round) = game.first_impossible_round(bag) {
+                let (colour, drawn) = bag.first_overdrawn(&game.rounds[round]).unwrap();
+                impossible.push(Impossible {
+                    game: game.id,
+                    round,
+                    colour,
+                    drawn,
+                    available: bag.get(colour).unwrap_or(0),
+                });
+            }
+        }
+
+        let mut top_power: Vec<(u32, u64)> =
+            games.iter().map(|g| (g.id, g.power_for(bag))).collect();
+        // Highest power first, lowest id breaks ties
+        top_power.sort_by_key(|&(id, power)| (std::cmp::Reverse(power), id));
+        top_power.truncate(top);
+
+        Report {
+            games: games.len(),
+            min_bags,
+            impossible,
+            top_power,
+            totals,
+        }
+    }
+
+    /// Renders the report as plain text tables.
+    pub fn to_table(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "games: {}", self.games).unwrap();
+
+        writeln!(out, "\nminimum bag per colour (count:games)").unwrap();
+        for (colour, counts) in &self.min_bags {
+            let spread: Vec<String> = counts.iter().map(|(n, g)| format!("{n}:{g}")).collect();
+            writeln!(out, "{:<8}{}", colour.name(), spread.join(" ")).unwrap();
+        }
+
+        writeln!(out, "\nimpossible games: {}", self.impossible.len()).unwrap();
+        if !self.impossible.is_empty() {
+            writeln!(
+                out,
+                "{:<6}{:<7}{:<8}{:<7}bag",
+                "game", "round", "colour", "drawn"
+            )
+            .unwrap();
+        }
+        for i in &self.impossible {
+            writeln!(
+                out,
+                "{:<6}{:<7}{:<8}{:<7}{}",
+                i.game,
+                i.round + 1,
+                i.colour.name(),
+                i.drawn,
+                i.available
+            )
+            .unwrap();
+        }
+
+        writeln!(out, "\nhighest power games\n{:<6}power", "game").unwrap();
+        for (game, power) in &self.top_power {
+            writeln!(out, "{game:<6}{power}").unwrap();
+        }
+
+        writeln!(out, "\ncubes drawn per colour").unwrap();
+        for (colour, total) in &self.totals {
+            writeln!(out, "{:<8}{total}", colour.name()).unwrap();
+        }
+
+        out
+    }
+
+    /// Renders the report as a JSON object. Rounds are one based, as in the table, and each
+    /// colour's minimum counts are an array in ascending order of count.
+    pub fn to_json(&self) -> serde_json::Value {
+        let min_bags: serde_json::Map<String, serde_json::Value> = self
+            .min_bags
+            .iter()
+            .map(|(colour, counts)| {
+                let counts: Vec<serde_json::Value> = counts
+                    .iter()
+                    .map(|(n, g)| json!({ "count": n, "games": g }))
+                    .collect();
+                (colour.name().to_string(), json!(counts))
+            })
+            .collect();
+
+        let impossible: Vec<serde_json::Value> = self
+            .impossible
+            .iter()
+            .map(|i| {
+                json!({
+                    "game": i.game,
+                    "round": i.round + 1,
+                    "colour": i.colour.name(),
+                    "drawn": i.drawn,
+                    "available": i.available,
+                })
+            })
+            .collect();
+
+        let top_power: Vec<serde_json::Value> = self
+            .top_power
+            .iter()
+            .map(|(game, power)| json!({ "game": game, "power": power }))
+            .collect();
+
+        let totals: serde_json::Map<String, serde_json::Value> = self
+            .totals
+            .iter()
+            .map(|(colour, total)| (colour.name().to_string(), json!(total)))
+            .collect();
+
+        json!({
+            "games": self.games,
+            "min_bags": min_bags,
+            "impossible": impossible,
+            "top_power": top_power,
+            "totals": totals,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_games;
+
+    const SAMPLE: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+
+    fn sample() -> Report {
+        Report::new(&parse_games(SAMPLE).unwrap(), &Bag::puzzle(), 3)
+    }
+
+    #[test]
+    fn sample_report() {
+        let report = sample();
+        assert_eq!(report.games, 5);
+
+        assert_eq!(report.min_bags[0].0, Colour::BLUE);
+        assert_eq!(
+            report.min_bags[0].1,
+            BTreeMap::from([(2, 1), (4, 1), (6, 2), (15, 1)])
+        );
+
+        assert_eq!(
+            report.impossible,
+            vec![
+                Impossible {
+                    game: 3,
+                    round: 0,
+                    colour: Colour::RED,
+                    drawn: 20,
+                    available: 12,
+                },
+                Impossible {
+                    game: 4,
+                    round: 2,
+                    colour: Colour::BLUE,
+                    drawn: 15,
+                    available: 14,
+                },
+            ]
+        );
+
+        assert_eq!(report.top_power, vec![(3, 1560), (4, 630), (1, 48)]);
+        assert_eq!(
+            report.totals,
+            vec![(Colour::BLUE, 50), (Colour::RED, 61), (Colour::GREEN, 48)]
+        );
+    }
+
+    #[test]
+    fn unknown_colours_are_reported() {
+        let games = parse_games("Game 9: 1 red; 2 teal").unwrap();
+        let report = Report::new(&games, &Bag::puzzle(), 1);
+        assert_eq!(report.impossible[0].colour, Colour::intern("teal"));
+        assert_eq!(report.impossible[0].available, 0);
+        assert_eq!(report.top_power, vec![(9, 0)]);
+    }
+
+    #[test]
+    fn undrawn_colours_count_as_zero() {
+        let games = parse_games("Game 1: 2 red\nGame 2: 3 teal; 1 red\nGame 3: 3 teal").unwrap();
+        let report = Report::new(&games, &Bag::puzzle(), 0);
+        assert_eq!(
+            report.min_bags,
+            vec![
+                (Colour::RED, BTreeMap::from([(0, 1), (1, 1), (2, 1)])),
+                (Colour::intern("teal"), BTreeMap::from([(0, 1), (3, 2)])),
+            ]
+        );
+        assert!(report.to_table().contains("red     0:1 1:1 2:1\n"));
+    }
+
+    #[test]
+    fn renderings() {
+        let report = sample();
+        let table = report.to_table();
+        assert!(table.contains("blue    2:1 4:1 6:2 15:1\n"));
+        assert!(table.contains("3     1      red     20     12\n"));
+
+        let json = report.to_json();
+        assert_eq!(json["impossible"][1]["round"], 3);
+        // Ordered by count, so 14 comes before 20 rather than after "1"
+        assert_eq!(
+            json["min_bags"]["red"],
+            json!([
+                { "count": 1, "games": 1 },
+                { "count": 4, "games": 1 },
+                { "count": 6, "games": 1 },
+                { "count": 14, "games": 1 },
+                { "count": 20, "games": 1 },
+            ])
+        );
+        assert_eq!(json["totals"]["green"], 48);
+        assert_eq!(json["top_power"][0]["power"], 1560);
+    }
+}
@@ -0,0 +1,84 @@
+//! Part 1 on the real input, `Vec<&str>` with `chars().nth(col)` lookups versus
+//! `day_03::schematic::Schematic`, parsing included.
+//!
+//! The string version is the original solution, kept here only as a baseline. It mixes byte
+//! offsets with char indices so is only correct for ASCII input, which the real input is. Set
+//! `AOC_BENCH_ITERS` to change the number of runs (20 by default).
+//!
+//! Run with `cargo bench --bench grid`.
+
+use day_03::schematic::Schematic;
+use std::time::Instant;
+
+const INPUT: &str = include_str!("../src/bin/input.txt");
+
+fn is_symbol(c: char) -> bool {
+    c != '.' && !c.is_ascii_digit()
+}
+
+/// The original approach: every cell lookup walks the row from the start.
+fn solve_strings(input: &str) -> u64 {
+    let schematic: Vec<&str> = input.lines().filter(|a| !a.is_empty()).collect();
+    let max_row = schematic.len() - 1;
+    let max_col = schematic[0].len() - 1;
+    let mut sum = 0;
+
+    for (row, line) in schematic.iter().enumerate() {
+        let indices: Vec<usize> = line
+            .match_indices(|a: char| a.is_ascii_digit())
+            .map(|(a, _)| a)
+            .collect();
+        let starts = indices
+            .iter()
+            .filter(|&&idx| idx == 0 || !indices.contains(&(idx - 1)));
+
+        for &col in starts {
+            let width = line
+                .chars()
+                .skip(col)
+                .take_while(char::is_ascii_digit)
+                .count();
+            let mut valid = false;
+            for r in row.saturating_sub(1)..=(row + 1).min(max_row) {
+                for c in col.saturating_sub(1)..=(col + width).min(max_col) {
+                    valid |= is_symbol(schematic.get(r).unwrap().chars().nth(c).unwrap());
+                }
+            }
+            if valid {
+                let digits: String = line.chars().skip(col).take(width).collect();
+                sum += digits.parse::<u64>().unwrap();
+            }
+        }
+    }
+    sum
+}
+
+/// The library solver: parse into a `Schematic`, then sum the numbers touching a symbol.
+fn solve_schematic(input: &str) -> u64 {
+    let schematic: Schematic = input.parse().unwrap();
+    schematic.part_sum()
+}
+
+/// Returns the mean seconds per run of `f`, checking every run gives `expected`.
+fn time(iters: u32, expected: u64, f: impl Fn(&str) -> u64) -> f64 {
+    let start = Instant::now();
+    for _ in 0..iters {
+        assert_eq!(f(std::hint::black_box(INPUT)), expected);
+    }
+    start.elapsed().as_secs_f64() / iters as f64
+}
+
+fn main() {
+    let iters = std::env::var("AOC_BENCH_ITERS")
+        .ok()
+        .map(|a| a.parse().expect("Expected a number of runs"))
+        .unwrap_or(20);
+    let expected = solve_schematic(INPUT);
+
+    let strings = time(iters, expected, solve_strings);
+    let schematic = time(iters, expected, solve_schematic);
+
+    println!("  strings: {:>10.1} us/run", strings * 1e6);
+    println!("schematic: {:>10.1} us/run", schematic * 1e6);
+    println!("  speedup: {:>10.1}x", strings / schematic);
+}
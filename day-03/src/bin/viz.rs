@@ -0,0 +1,48 @@
+//! Draws the day 3 schematic with part numbers, rejected numbers, symbols and gears coloured.
+//!
+//! Usage: `viz [--svg] [--output <file>] [--input <file>] [gear rule flags]`, where the gear
+//! rule flags are the same `--gear-symbols`, `--neighbours` and `--combine` understood by
+//! `part2`. ANSI output goes to the terminal by default, `--svg` switches to an SVG in which
+//! hovering a gear shows its value.
+
+use day_03::gear::GearRule;
+use day_03::render;
+use day_03::schematic::Schematic;
+
+fn main() {
+    let mut svg = false;
+    let mut output = None;
+    let mut path = None;
+    let mut rule_args = vec![];
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--svg" => svg = true,
+            "--output" => output = Some(args.next().expect("Missing value for --output")),
+            "--input" => path = Some(args.next().expect("Missing value for --input")),
+            _ => rule_args.push(arg),
+        }
+    }
+
+    let rule = GearRule::puzzle()
+        .with_args(rule_args)
+        .unwrap_or_else(|e| panic!("{e}"));
+
+    let input = match path {
+        Some(path) => std::fs::read_to_string(path).expect("Failed to read input"),
+        None => include_str!("./input.txt").to_string(),
+    };
+    let schematic: Schematic = input
+        .parse()
+        .unwrap_or_else(|e| panic!("Bad schematic: {e}"));
+
+    let picture = match svg {
+        true => render::svg(&schematic, &rule),
+        false => render::ansi(&schematic, &rule),
+    };
+    match output {
+        Some(path) => std::fs::write(path, picture).expect("Failed to write output"),
+        None => print!("{picture}"),
+    }
+}
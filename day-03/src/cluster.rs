@@ -0,0 +1,201 @@
+//! Connected clusters of part numbers and symbols.
+//!
+//! Numbers and symbols are the nodes of the schematic's adjacency graph, with an edge between
+//! each symbol and every number touching it. A cluster is a connected component of that graph,
+//! found with a union-find over every edge.
+
+use crate::grid::Point;
+use crate::schematic::Schematic;
+
+/// Disjoint sets over `0..n` with path halving and union by size.
+struct UnionFind {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+}
+
+impl UnionFind {
+    fn new(n: usize) -> UnionFind {
+        UnionFind {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+        }
+    }
+
+    fn find(&mut self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            self.parent[x] = self.parent[self.parent[x]];
+            x = self.parent[x];
+        }
+        x
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return;
+        }
+        if self.size[a] < self.size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cluster {
+    /// Indices into `Schematic::numbers`, in order.
+    pub numbers: Vec<usize>,
+    /// Indices into `Schematic::symbols`, in order.
+    pub symbols: Vec<usize>,
+    /// Sum of the numbers' values.
+    pub sum: u64,
+    /// Top left corner of the smallest box covering every cell in the cluster.
+    pub min: Point,
+    /// Bottom right corner of that box, inclusive.
+    pub max: Point,
+}
+
+impl Cluster {
+    /// The number of numbers and symbols in the cluster.
+    pub fn size(&self) -> usize {
+        self.numbers.len() + self.symbols.len()
+    }
+
+    pub fn width(&self) -> usize {
+        self.max.col - self.min.col + 1
+    }
+
+    pub fn height(&self) -> usize {
+        self.max.row - self.min.row + 1
+    }
+}
+
+impl Schematic {
+    /// Returns the connected clusters of numbers and symbols, ordered by their first symbol.
+    ///
+    /// Numbers with no symbol next to them and symbols with no number next to them aren't in any
+    /// cluster.
+    pub fn clusters(&self) -> Vec<Cluster> {
+        // Numbers are nodes `0..numbers.len()`, symbols follow on after them
+        let offset = self.numbers.len();
+        let mut sets = UnionFind::new(offset + self.symbols.len());
+        for symbol in 0..self.symbols.len() {
+            for &number in self.numbers_of(symbol) {
+                sets.union(number, offset + symbol);
+            }
+        }
+
+        // Symbols are visited in order, so clusters come out ordered by their first symbol
+        let mut cluster_of: Vec<Option<usize>> = vec![None; offset + self.symbols.len()];
+        let mut clusters: Vec<Cluster> = vec![];
+        for symbol in (0..self.symbols.len()).filter(|&s| !self.numbers_of(s).is_empty()) {
+            let root = sets.find(offset + symbol);
+            let index = match cluster_of[root] {
+                Some(index) => index,
+                None => {
+                    cluster_of[root] = Some(clusters.len());
+                    let pos = self.symbols[symbol].pos;
+                    clusters.push(Cluster {
+                        numbers: vec![],
+                        symbols: vec![],
+                        sum: 0,
+                        min: pos,
+                        max: pos,
+                    });
+                    clusters.len() - 1
+                }
+            };
+            let cluster = &mut clusters[index];
+            cluster.symbols.push(symbol);
+            cover(cluster, self.symbols[symbol].pos);
+        }
+
+        for number in (0..self.numbers.len()).filter(|&n| !self.symbols_of(n).is_empty()) {
+            let root = sets.find(number);
+            let index = cluster_of[root].expect("A part number shares a cluster with its symbols");
+            let cluster = &mut clusters[index];
+            let part = &self.numbers[number];
+            cluster.numbers.push(number);
+            cluster.sum += u64::from(part.value);
+            cover(cluster, Point::new(part.row, part.col_span.start));
+            cover(cluster, Point::new(part.row, part.col_span.end - 1));
+        }
+
+        clusters
+    }
+}
+
+/// Grows `cluster`'s bounding box to include `p`.
+fn cover(cluster: &mut Cluster, p: Point) {
+    cluster.min.row = cluster.min.row.min(p.row);
+    cluster.min.col = cluster.min.col.min(p.col);
+    cluster.max.row = cluster.max.row.max(p.row);
+    cluster.max.col = cluster.max.col.max(p.col);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PUZZLE_EXAMPLE;
+
+    #[test]
+    fn union_find() {
+        let mut sets = UnionFind::new(5);
+        sets.union(0, 1);
+        sets.union(3, 4);
+        sets.union(1, 4);
+        assert_eq!(sets.find(0), sets.find(3));
+        assert_ne!(sets.find(0), sets.find(2));
+    }
+
+    #[test]
+    fn puzzle_example() {
+        let schematic: Schematic = PUZZLE_EXAMPLE.parse().unwrap();
+        let clusters = schematic.clusters();
+
+        let sizes: Vec<usize> = clusters.iter().map(Cluster::size).collect();
+        assert_eq!(sizes, vec![3, 2, 2, 2, 2, 3]);
+        let sums: Vec<u64> = clusters.iter().map(|c| c.sum).collect();
+        assert_eq!(sums, vec![502, 633, 617, 592, 664, 755 + 598]);
+
+        assert_eq!(
+            clusters[0],
+            Cluster {
+                numbers: vec![0, 2],
+                symbols: vec![0],
+                sum: 502,
+                min: Point::new(0, 0),
+                max: Point::new(2, 3),
+            }
+        );
+        assert_eq!((clusters[5].width(), clusters[5].height()), (4, 3));
+        assert_eq!(clusters.iter().map(|c| c.sum).sum::<u64>(), 4361);
+    }
+
+    #[test]
+    fn chains_merge() {
+        // Two symbols sharing 22 and a third symbol reached through 5
+        let schematic: Schematic = "1.....\n.#.$..\n..22*5\n.....%\n......".parse().unwrap();
+        let clusters = schematic.clusters();
+
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(clusters[0].symbols, vec![0, 1, 2, 3]);
+        assert_eq!(clusters[0].sum, 1 + 22 + 5);
+        assert_eq!(clusters[0].size(), 7);
+        assert_eq!(clusters[0].min, Point::new(0, 0));
+        assert_eq!(clusters[0].max, Point::new(3, 5));
+    }
+
+    #[test]
+    fn loners_are_left_out() {
+        let schematic: Schematic = "#...\n..5.\n9..*".parse().unwrap();
+        let clusters = schematic.clusters();
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(clusters[0].numbers, vec![0]);
+        assert_eq!(clusters[0].symbols, vec![1]);
+        assert!(Schematic::new("....".parse().unwrap())
+            .clusters()
+            .is_empty());
+    }
+}
@@ -0,0 +1,264 @@
+//! An editable schematic that keeps both puzzle answers up to date as cells change.
+//!
+//! Changing a cell can only change the numbers in its own row, so only numbers in that row and
+//! the rows either side can gain or lose a symbol, and only symbols in those rows can gain or
+//! lose a number. Each row's contribution to the part sum and the gear sum is cached and just
+//! those three rows are recomputed after an edit.
+
+use crate::gear::GearRule;
+use crate::grid::{Grid, Point};
+use crate::schematic::{is_symbol, row_numbers, PartNumber};
+use std::ops::Range;
+
+#[derive(Debug, Clone)]
+pub struct Editor {
+    grid: Grid<char>,
+    rule: GearRule,
+    /// Sum of the part numbers in each row.
+    row_parts: Vec<u64>,
+    /// Sum of the values of the gears in each row.
+    row_gears: Vec<u64>,
+    /// The row sums added up exactly, so a row can always be taken back out. They only saturate
+    /// when read.
+    part_sum: u128,
+    gear_sum: u128,
+}
+
+impl Editor {
+    /// Panics if a number is too large for a `u32`.
+    pub fn new(grid: Grid<char>, rule: GearRule) -> Editor {
+        let mut editor = Editor {
+            row_parts: vec![0; grid.height()],
+            row_gears: vec![0; grid.height()],
+            grid,
+            rule,
+            part_sum: 0,
+            gear_sum: 0,
+        };
+        for row in 0..editor.grid.height() {
+            editor.update_row(row);
+        }
+        editor
+    }
+
+    pub fn grid(&self) -> &Grid<char> {
+        &self.grid
+    }
+
+    pub fn rule(&self) -> &GearRule {
+        &self.rule
+    }
+
+    /// The sum of every number touching a symbol.
+    pub fn part_sum(&self) -> u64 {
+        saturate(self.part_sum)
+    }
+
+    /// The sum of every gear value under the editor's rule, saturating at `u64::MAX`.
+    pub fn gear_sum(&self) -> u64 {
+        saturate(self.gear_sum)
+    }
+
+    /// Sets the cell at `p` to `ch`, returning what was there before.
+    ///
+    /// Panics if `p` is outside the grid, or if the edit makes a number too large for a `u32`.
+    pub fn set(&mut self, p: Point, ch: char) -> char {
+        let old = std::mem::replace(&mut self.grid[p], ch);
+        if old != ch {
+            for row in self.rows_around(p.row) {
+                self.update_row(row);
+            }
+        }
+        old
+    }
+
+    /// Recomputes the cached sums for `row` and folds the difference into the totals.
+    fn update_row(&mut self, row: usize) {
+        let parts = self.parts_in(row);
+        let gears = self.gears_in(row);
+
+        self.part_sum = self.part_sum - u128::from(self.row_parts[row]) + u128::from(parts);
+        self.gear_sum = self.gear_sum - u128::from(self.row_gears[row]) + u128::from(gears);
+        self.row_parts[row] = parts;
+        self.row_gears[row] = gears;
+    }
+
+    /// Returns `row` and the rows either side of it that are in the grid.
+    fn rows_around(&self, row: usize) -> Range<usize> {
+        row.saturating_sub(1)..(row + 2).min(self.grid.height())
+    }
+
+    fn numbers_in(&self, row: usize) -> Vec<PartNumber> {
+        row_numbers(row, self.grid.row(row)).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    fn parts_in(&self, row: usize) -> u64 {
+        let symbols: Vec<Point> = self
+            .rows_around(row)
+            .flat_map(|r| {
+                let cells = self.grid.row(r).iter().enumerate();
+                cells
+                    .filter(|(_, &c)| is_symbol(c))
+                    .map(move |(col, _)| Point::new(r, col))
+            })
+            .collect();
+
+        self.numbers_in(row)
+            .into_iter()
+            .filter(|n| symbols.iter().any(|&p| n.touches(p)))
+            .map(|n| u64::from(n.value))
+            .fold(0, u64::saturating_add)
+    }
+
+    fn gears_in(&self, row: usize) -> u64 {
+        let candidates: Vec<(usize, char)> = self
+            .grid
+            .row(row)
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| self.rule.symbols.contains(c))
+            .map(|(col, &c)| (col, c))
+            .collect();
+        if candidates.is_empty() {
+            return 0;
+        }
+
+        let nearby: Vec<PartNumber> = self
+            .rows_around(row)
+            .flat_map(|r| self.numbers_in(r))
+            .collect();
+
+        candidates
+            .into_iter()
+            .filter_map(|(col, ch)| {
+                let touching: Vec<u32> = nearby
+                    .iter()
+                    .filter(|n| n.touches(Point::new(row, col)))
+                    .map(|n| n.value)
+                    .collect();
+                self.rule
+                    .matches(ch, touching.len())
+                    .then(|| self.rule.combine.apply(touching.into_iter()))
+            })
+            .fold(0, u64::saturating_add)
+    }
+}
+
+fn saturate(total: u128) -> u64 {
+    u64::try_from(total).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gear::{Combine, Neighbours};
+    use crate::schematic::Schematic;
+    use crate::PUZZLE_EXAMPLE;
+    use proptest::prelude::*;
+
+    fn editor(input: &str) -> Editor {
+        Editor::new(input.parse().unwrap(), GearRule::puzzle())
+    }
+
+    #[test]
+    fn starts_with_the_puzzle_answers() {
+        let editor = editor(PUZZLE_EXAMPLE);
+        assert_eq!(editor.part_sum(), 4361);
+        assert_eq!(editor.gear_sum(), 467835);
+    }
+
+    #[test]
+    fn edits() {
+        let mut editor = editor(PUZZLE_EXAMPLE);
+
+        // 114 gets a symbol
+        assert_eq!(editor.set(Point::new(1, 8), '#'), '.');
+        assert_eq!(editor.part_sum(), 4361 + 114);
+
+        // The first gear loses 35
+        editor.set(Point::new(2, 2), '.');
+        editor.set(Point::new(2, 3), '.');
+        assert_eq!(editor.part_sum(), 4361 + 114 - 35);
+        assert_eq!(editor.gear_sum(), 451490);
+
+        // Joining 617 and the gear next to it into a longer number removes a symbol
+        editor.set(Point::new(4, 3), '9');
+        assert_eq!(editor.part_sum(), 4361 + 114 - 35 - 617);
+
+        // Writing what's already there changes nothing
+        assert_eq!(editor.set(Point::new(0, 0), '4'), '4');
+        assert_eq!(editor.part_sum(), 4361 + 114 - 35 - 617);
+        assert_eq!(editor.gear_sum(), 451490);
+    }
+
+    #[test]
+    fn splitting_a_number() {
+        let mut editor = editor("12*34");
+        assert_eq!(editor.gear_sum(), 12 * 34);
+
+        editor.set(Point::new(0, 4), '*');
+        assert_eq!(editor.part_sum(), 15);
+        assert_eq!(editor.gear_sum(), 12 * 3);
+
+        editor.set(Point::new(0, 0), '*');
+        assert_eq!(editor.part_sum(), 5);
+        assert_eq!(editor.gear_sum(), 2 * 3);
+    }
+
+    #[test]
+    fn large_numbers() {
+        let mut editor = editor("4000000000*4000000000");
+        assert_eq!(editor.part_sum(), 8_000_000_000);
+        assert_eq!(editor.gear_sum(), 16_000_000_000_000_000_000);
+
+        editor.set(Point::new(0, 0), '.');
+        assert_eq!(editor.part_sum(), 4_000_000_000);
+        assert_eq!(editor.gear_sum(), 0);
+    }
+
+    fn cell() -> impl Strategy<Value = char> {
+        prop::sample::select(vec!['.', '.', '.', '1', '2', '5', '9', '*', '*', '#', '×'])
+    }
+
+    fn grid() -> impl Strategy<Value = Grid<char>> {
+        (1..8usize, 1..8usize).prop_flat_map(|(width, height)| {
+            prop::collection::vec(prop::collection::vec(cell(), width), height)
+                .prop_map(|rows| Grid::from_rows(rows).unwrap())
+        })
+    }
+
+    fn rule() -> impl Strategy<Value = GearRule> {
+        prop_oneof![
+            Just(GearRule::puzzle()),
+            Just(GearRule {
+                symbols: vec!['*', '×'],
+                neighbours: Neighbours::AtLeast(1),
+                combine: Combine::Sum,
+            }),
+            Just(GearRule {
+                symbols: vec!['#'],
+                neighbours: Neighbours::Exactly(3),
+                combine: Combine::Max,
+            }),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn matches_full_recompute(
+            grid in grid(),
+            rule in rule(),
+            edits in prop::collection::vec((0..8usize, 0..8usize, cell()), 0..40),
+        ) {
+            let mut editor = Editor::new(grid, rule.clone());
+            for (row, col, ch) in edits {
+                let p = Point::new(row % editor.grid().height(), col % editor.grid().width());
+                editor.set(p, ch);
+
+                let full = Schematic::new(editor.grid().clone());
+                prop_assert_eq!(editor.part_sum(), full.part_sum());
+                prop_assert_eq!(editor.gear_sum(), full.gear_sum(&rule));
+            }
+        }
+    }
+}
@@ -0,0 +1,202 @@
+//! Which symbols count as gears and what they're worth.
+//!
+//! The puzzle's rule is "a `*` touching exactly two numbers, worth their product". A `GearRule`
+//! makes each piece of that swappable so variant questions can be asked of the same schematic.
+
+use std::str::FromStr;
+
+/// How many numbers a symbol must touch to count.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Neighbours {
+    Exactly(usize),
+    AtLeast(usize),
+}
+
+impl Neighbours {
+    pub fn allows(self, count: usize) -> bool {
+        match self {
+            Neighbours::Exactly(k) => count == k,
+            Neighbours::AtLeast(k) => count >= k,
+        }
+    }
+}
+
+impl FromStr for Neighbours {
+    type Err = String;
+
+    /// Parses `exactly:K` or `at-least:K`; a bare `K` means exactly.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |k: &str| {
+            k.parse()
+                .map_err(|_| format!("Expected a neighbour count, got {k:?}"))
+        };
+        match s.split_once(':') {
+            Some(("exactly", k)) => Ok(Neighbours::Exactly(parse(k)?)),
+            Some(("at-least", k)) => Ok(Neighbours::AtLeast(parse(k)?)),
+            Some((kind, _)) => Err(format!("Unknown neighbour rule: {kind}")),
+            None => Ok(Neighbours::Exactly(parse(s)?)),
+        }
+    }
+}
+
+/// How the numbers touching a gear are folded into its value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Combine {
+    Product,
+    Sum,
+    Max,
+}
+
+impl Combine {
+    /// Folds `values` into one, saturating at `u64::MAX`. A gear touching three or more large
+    /// numbers easily overflows a product.
+    pub fn apply(self, values: impl Iterator<Item = u32>) -> u64 {
+        let values = values.map(u64::from);
+        match self {
+            Combine::Product => values.fold(1, u64::saturating_mul),
+            Combine::Sum => values.fold(0, u64::saturating_add),
+            Combine::Max => values.max().unwrap_or(0),
+        }
+    }
+}
+
+impl FromStr for Combine {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "product" => Ok(Combine::Product),
+            "sum" => Ok(Combine::Sum),
+            "max" => Ok(Combine::Max),
+            _ => Err(format!("Unknown combining function: {s}")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GearRule {
+    /// Symbols that can be gears.
+    pub symbols: Vec<char>,
+    pub neighbours: Neighbours,
+    pub combine: Combine,
+}
+
+impl GearRule {
+    /// The puzzle's rule: a `*` touching exactly two numbers is worth their product.
+    pub fn puzzle() -> GearRule {
+        GearRule {
+            symbols: vec!['*'],
+            neighbours: Neighbours::Exactly(2),
+            combine: Combine::Product,
+        }
+    }
+
+    /// Returns true if a `symbol` touching `count` numbers is a gear under this rule.
+    pub fn matches(&self, symbol: char, count: usize) -> bool {
+        self.symbols.contains(&symbol) && self.neighbours.allows(count)
+    }
+
+    /// Applies `--gear-symbols <chars>`, `--neighbours <exactly:K|at-least:K>` and
+    /// `--combine <product|sum|max>` flags on top of `self`.
+    pub fn with_args(mut self, args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut args = args.into_iter();
+        while let Some(flag) = args.next() {
+            let mut value = || args.next().ok_or(format!("Missing value for {flag}"));
+            match flag.as_str() {
+                "--gear-symbols" => self.symbols = value()?.chars().collect(),
+                "--neighbours" => self.neighbours = value()?.parse()?,
+                "--combine" => self.combine = value()?.parse()?,
+                _ => return Err(format!("Unknown argument: {flag}")),
+            }
+        }
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn neighbour_counts() {
+        assert_eq!("2".parse(), Ok(Neighbours::Exactly(2)));
+        assert_eq!("exactly:3".parse(), Ok(Neighbours::Exactly(3)));
+        assert_eq!("at-least:1".parse(), Ok(Neighbours::AtLeast(1)));
+        assert!("most:1".parse::<Neighbours>().is_err());
+        assert!("at-least:x".parse::<Neighbours>().is_err());
+
+        assert!(Neighbours::AtLeast(2).allows(3));
+        assert!(!Neighbours::Exactly(2).allows(3));
+    }
+
+    #[test]
+    fn combining() {
+        let values = || [3, 4, 5].into_iter();
+        assert_eq!(Combine::Product.apply(values()), 60);
+        assert_eq!(Combine::Sum.apply(values()), 12);
+        assert_eq!(Combine::Max.apply(values()), 5);
+        assert_eq!(
+            Combine::Product.apply([99999, 99999].into_iter()),
+            9999800001
+        );
+        assert_eq!(
+            Combine::Product.apply([u32::MAX, u32::MAX, 2].into_iter()),
+            u64::MAX
+        );
+        // A zero after saturating still wins
+        assert_eq!(
+            Combine::Product.apply([u32::MAX, u32::MAX, 0].into_iter()),
+            0
+        );
+    }
+
+    #[test]
+    fn saturating_gears() {
+        use crate::schematic::Schematic;
+        use crate::stream::scan;
+
+        // Eight 9999s around one gear, and two such gears overflow their sum too
+        let input = "9999.9999\n9999*9999\n9999.9999";
+        let rule = GearRule {
+            neighbours: Neighbours::AtLeast(1),
+            ..GearRule::puzzle()
+        };
+        let schematic: Schematic = input.parse().unwrap();
+        assert_eq!(schematic.gear_sum(&rule), u64::MAX);
+        let totals = scan(input.as_bytes(), &rule, |_| ()).unwrap();
+        assert_eq!(totals.gear_sum, u64::MAX);
+
+        let twice: Schematic = "9999.9999.9999.9999\n9999*9999.9999*9999\n9999.9999.9999.9999"
+            .parse()
+            .unwrap();
+        assert_eq!(twice.gear_sum(&rule), u64::MAX);
+    }
+
+    #[test]
+    fn args() {
+        let args = [
+            "--gear-symbols",
+            "*#",
+            "--neighbours",
+            "at-least:1",
+            "--combine",
+            "max",
+        ];
+        let rule = GearRule::puzzle()
+            .with_args(args.map(String::from))
+            .unwrap();
+        assert_eq!(
+            rule,
+            GearRule {
+                symbols: vec!['*', '#'],
+                neighbours: Neighbours::AtLeast(1),
+                combine: Combine::Max,
+            }
+        );
+        assert!(rule.matches('#', 4));
+        assert!(!rule.matches('$', 4));
+        assert!(GearRule::puzzle()
+            .with_args(["--combine".to_string()])
+            .is_err());
+    }
+}
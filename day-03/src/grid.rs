@@ -0,0 +1,349 @@
+//! A dense, row-major 2D grid with O(1) indexing.
+
+use std::fmt;
+use std::ops::{Index, IndexMut};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl Point {
+    pub fn new(row: usize, col: usize) -> Point {
+        Point { row, col }
+    }
+}
+
+/// Offsets of the four orthogonal neighbours.
+const NEIGHBOURS_4: [(isize, isize); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+
+/// Offsets of all eight neighbours, row by row.
+const NEIGHBOURS_8: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+/// Rows handed to `Grid::from_rows` weren't all the same length.
+#[derive(Debug, PartialEq, Eq)]
+pub struct RaggedRowsError {
+    /// Zero based index of the first row whose width differs from the first row.
+    pub row: usize,
+    pub expected: usize,
+    pub found: usize,
+}
+
+impl fmt::Display for RaggedRowsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "row {} is {} cells wide, expected {}",
+            self.row + 1,
+            self.found,
+            self.expected
+        )
+    }
+}
+
+impl<T> Grid<T> {
+    /// Returns a `width` by `height` grid with every cell set to `fill`.
+    pub fn new(width: usize, height: usize, fill: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// Builds a grid from equally sized rows.
+    pub fn from_rows(rows: impl IntoIterator<Item = Vec<T>>) -> Result<Grid<T>, RaggedRowsError> {
+        let mut cells = vec![];
+        let mut width = 0;
+        let mut height = 0;
+
+        for (row, cols) in rows.into_iter().enumerate() {
+            if row == 0 {
+                width = cols.len();
+            } else if cols.len() != width {
+                return Err(RaggedRowsError {
+                    row,
+                    expected: width,
+                    found: cols.len(),
+                });
+            }
+            cells.extend(cols);
+            height += 1;
+        }
+
+        Ok(Grid {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        p.row < self.height && p.col < self.width
+    }
+
+    /// Returns the cell at `p`, `None` if out of bounds.
+    pub fn get(&self, p: Point) -> Option<&T> {
+        match self.contains(p) {
+            true => Some(&self.cells[p.row * self.width + p.col]),
+            false => None,
+        }
+    }
+
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        match self.contains(p) {
+            true => Some(&mut self.cells[p.row * self.width + p.col]),
+            false => None,
+        }
+    }
+
+    /// Returns the in-bounds points at the given offsets from `p`.
+    fn offsets(&self, p: Point, offsets: &'static [(isize, isize)]) -> impl Iterator<Item = Point> {
+        let (width, height) = (self.width, self.height);
+        offsets.iter().filter_map(move |&(dr, dc)| {
+            let row = p.row.checked_add_signed(dr)?;
+            let col = p.col.checked_add_signed(dc)?;
+            (row < height && col < width).then_some(Point { row, col })
+        })
+    }
+
+    /// Returns the in-bounds orthogonal neighbours of `p`.
+    pub fn neighbours_4(&self, p: Point) -> impl Iterator<Item = Point> {
+        self.offsets(p, &NEIGHBOURS_4)
+    }
+
+    /// Returns the in-bounds orthogonal and diagonal neighbours of `p`.
+    pub fn neighbours_8(&self, p: Point) -> impl Iterator<Item = Point> {
+        self.offsets(p, &NEIGHBOURS_8)
+    }
+
+    /// Returns the in-bounds points bordering a horizontal run of `width` cells starting at `p`,
+    /// corners included.
+    pub fn span_neighbours(&self, p: Point, width: usize) -> impl Iterator<Item = Point> {
+        let (grid_width, grid_height) = (self.width, self.height);
+        let rows = p.row.saturating_sub(1)..(p.row + 2).min(grid_height);
+        let cols = p.col.saturating_sub(1)..(p.col + width + 1).min(grid_width);
+
+        rows.flat_map(move |row| cols.clone().map(move |col| Point { row, col }))
+            .filter(move |n| n.row != p.row || n.col < p.col || n.col >= p.col + width)
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn row_mut(&mut self, row: usize) -> &mut [T] {
+        &mut self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // `chunks` panics on 0, an empty grid simply has no rows
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(col).step_by(self.width.max(1))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|col| self.column(col))
+    }
+
+    /// Returns every point in row-major order.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |col| Point { row, col }))
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point) -> &T {
+        assert!(self.contains(p), "{p:?} is outside the grid");
+        &self.cells[p.row * self.width + p.col]
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, p: Point) -> &mut T {
+        assert!(self.contains(p), "{p:?} is outside the grid");
+        &mut self.cells[p.row * self.width + p.col]
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    /// Writes one line per row with cells side by side.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, row) in self.rows().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Grid<char> {
+    type Err = RaggedRowsError;
+
+    /// Parses one row per non-empty line, one cell per `char`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Grid::from_rows(
+            s.lines()
+                .filter(|a| !a.is_empty())
+                .map(|line| line.chars().collect()),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "abc
+def
+ghi";
+
+    fn sorted(points: impl Iterator<Item = Point>) -> Vec<(usize, usize)> {
+        let mut points: Vec<_> = points.map(|p| (p.row, p.col)).collect();
+        points.sort();
+        points
+    }
+
+    #[test]
+    fn indexing() {
+        let grid: Grid<char> = SAMPLE.parse().unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 3));
+        assert_eq!(grid[Point::new(1, 2)], 'f');
+        assert_eq!(grid.get(Point::new(2, 0)), Some(&'g'));
+        assert_eq!(grid.get(Point::new(3, 0)), None);
+        assert_eq!(grid.get(Point::new(0, 3)), None);
+        assert_eq!(grid.to_string(), SAMPLE);
+    }
+
+    #[test]
+    fn mutation() {
+        let mut grid = Grid::new(2, 2, '.');
+        grid[Point::new(1, 0)] = '#';
+        *grid.get_mut(Point::new(0, 1)).unwrap() = '*';
+        assert_eq!(grid.to_string(), ".*\n#.");
+        assert!(grid.get_mut(Point::new(2, 2)).is_none());
+    }
+
+    #[test]
+    fn ragged_rows() {
+        assert_eq!(
+            "ab\nabc".parse::<Grid<char>>(),
+            Err(RaggedRowsError {
+                row: 1,
+                expected: 2,
+                found: 3,
+            })
+        );
+    }
+
+    #[test]
+    fn multi_byte_cells() {
+        // `§` and `×` are two bytes each in UTF-8 but one cell each here
+        let grid: Grid<char> = "a§c\n×ef".parse().unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[Point::new(0, 1)], '§');
+        assert_eq!(grid[Point::new(1, 0)], '×');
+        assert_eq!(grid[Point::new(1, 2)], 'f');
+        assert_eq!(grid.to_string(), "a§c\n×ef");
+        assert_eq!(
+            "§§\nabc".parse::<Grid<char>>(),
+            Err(RaggedRowsError {
+                row: 1,
+                expected: 2,
+                found: 3,
+            })
+        );
+    }
+
+    #[test]
+    fn neighbours() {
+        let grid: Grid<char> = SAMPLE.parse().unwrap();
+        assert_eq!(
+            sorted(grid.neighbours_4(Point::new(1, 1))),
+            vec![(0, 1), (1, 0), (1, 2), (2, 1)]
+        );
+        assert_eq!(grid.neighbours_8(Point::new(1, 1)).count(), 8);
+        assert_eq!(
+            sorted(grid.neighbours_8(Point::new(0, 0))),
+            vec![(0, 1), (1, 0), (1, 1)]
+        );
+        assert_eq!(
+            sorted(grid.neighbours_4(Point::new(2, 2))),
+            vec![(1, 2), (2, 1)]
+        );
+    }
+
+    #[test]
+    fn span_neighbours() {
+        let grid = Grid::new(5, 3, '.');
+        assert_eq!(
+            sorted(grid.span_neighbours(Point::new(1, 1), 2)),
+            vec![
+                (0, 0),
+                (0, 1),
+                (0, 2),
+                (0, 3),
+                (1, 0),
+                (1, 3),
+                (2, 0),
+                (2, 1),
+                (2, 2),
+                (2, 3)
+            ]
+        );
+        assert_eq!(
+            sorted(grid.span_neighbours(Point::new(0, 3), 2)),
+            vec![(0, 2), (1, 2), (1, 3), (1, 4)]
+        );
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let grid: Grid<char> = SAMPLE.parse().unwrap();
+        assert_eq!(grid.row(1), &['d', 'e', 'f']);
+        assert_eq!(grid.rows().count(), 3);
+        assert_eq!(grid.column(2).collect::<String>(), "cfi");
+        let columns: Vec<String> = grid.columns().map(|c| c.collect()).collect();
+        assert_eq!(columns, vec!["adg", "beh", "cfi"]);
+        assert_eq!(grid.points().nth(4), Some(Point::new(1, 1)));
+    }
+}
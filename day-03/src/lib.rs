@@ -0,0 +1,22 @@
+//! Shared schematic model for Advent of Code 2023, day 3.
+
+pub mod cluster;
+pub mod editor;
+pub mod gear;
+pub mod grid;
+pub mod render;
+pub mod schematic;
+pub mod stream;
+
+/// The example schematic from the puzzle text, shared by every module's tests.
+#[cfg(test)]
+const PUZZLE_EXAMPLE: &str = "467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
@@ -0,0 +1,215 @@
+//! ANSI and SVG pictures of a schematic.
+//!
+//! Every cell is classified from the schematic's own adjacency graph and gear rule, so the
+//! picture shows exactly what the solver counted.
+
+use crate::gear::GearRule;
+use crate::grid::{Grid, Point};
+use crate::schematic::Schematic;
+use std::fmt::Write;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Blank,
+    /// A digit of a number touching a symbol.
+    Part,
+    /// A digit of a number touching no symbols.
+    Rejected,
+    /// A symbol that isn't a gear.
+    Symbol,
+    /// A symbol that is a gear under the rule in use.
+    Gear,
+}
+
+impl Kind {
+    fn ansi(self) -> &'static str {
+        match self {
+            Kind::Blank => "\x1b[2m",
+            Kind::Part => "\x1b[32m",
+            Kind::Rejected => "\x1b[31m",
+            Kind::Symbol => "\x1b[33m",
+            Kind::Gear => "\x1b[1;35m",
+        }
+    }
+
+    fn svg(self) -> &'static str {
+        match self {
+            Kind::Blank => "#bbb",
+            Kind::Part => "#2a2",
+            Kind::Rejected => "#c33",
+            Kind::Symbol => "#c90",
+            Kind::Gear => "#a3c",
+        }
+    }
+}
+
+const RESET: &str = "\x1b[0m";
+
+/// Pixel size of one cell in the SVG.
+const CELL_WIDTH: usize = 10;
+const CELL_HEIGHT: usize = 16;
+
+/// Returns the kind of every cell, and the value of every gear by position.
+pub fn classify(schematic: &Schematic, rule: &GearRule) -> (Grid<Kind>, Vec<(Point, u64)>) {
+    let grid = &schematic.grid;
+    let mut kinds = Grid::new(grid.width(), grid.height(), Kind::Blank);
+
+    for (index, number) in schematic.numbers.iter().enumerate() {
+        let kind = match schematic.symbols_of(index).is_empty() {
+            true => Kind::Rejected,
+            false => Kind::Part,
+        };
+        for cell in &mut kinds.row_mut(number.row)[number.col_span.clone()] {
+            *cell = kind;
+        }
+    }
+    for symbol in &schematic.symbols {
+        kinds[symbol.pos] = Kind::Symbol;
+    }
+
+    let gears: Vec<(Point, u64)> = schematic
+        .gears(rule)
+        .map(|i| schematic.symbols[i].pos)
+        .zip(schematic.gear_values(rule))
+        .collect();
+    for &(pos, _) in &gears {
+        kinds[pos] = Kind::Gear;
+    }
+
+    (kinds, gears)
+}
+
+/// Renders the schematic with ANSI colours, one line per row.
+pub fn ansi(schematic: &Schematic, rule: &GearRule) -> String {
+    let (kinds, _) = classify(schematic, rule);
+    let mut out = String::new();
+
+    for (cells, kinds) in schematic.grid.rows().zip(kinds.rows()) {
+        // Only switch colour where the kind changes
+        let mut current = None;
+        for (&ch, &kind) in cells.iter().zip(kinds) {
+            if current != Some(kind) {
+                if current.is_some() {
+                    out.push_str(RESET);
+                }
+                out.push_str(kind.ansi());
+                current = Some(kind);
+            }
+            out.push(ch);
+        }
+        if current.is_some() {
+            out.push_str(RESET);
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Escapes the characters that can't appear as-is in SVG text.
+fn escape(ch: char) -> String {
+    match ch {
+        '&' => "&amp;".to_string(),
+        '<' => "&lt;".to_string(),
+        '>' => "&gt;".to_string(),
+        _ => ch.to_string(),
+    }
+}
+
+/// Renders the schematic as a standalone SVG. Hovering a gear shows its value.
+pub fn svg(schematic: &Schematic, rule: &GearRule) -> String {
+    let (kinds, gears) = classify(schematic, rule);
+    let grid = &schematic.grid;
+    let (width, height) = (grid.width() * CELL_WIDTH, grid.height() * CELL_HEIGHT);
+
+    let mut out = String::new();
+    writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" font-family="monospace" font-size="14" text-anchor="middle">"#
+    )
+    .unwrap();
+    writeln!(out, r##"<rect width="100%" height="100%" fill="#111"/>"##).unwrap();
+
+    for p in grid.points() {
+        // Blank cells are only ever `.`, leaving them out keeps the file small
+        let kind = kinds[p];
+        if kind == Kind::Blank || kind == Kind::Gear {
+            continue;
+        }
+        let (x, y) = (
+            p.col * CELL_WIDTH + CELL_WIDTH / 2,
+            (p.row + 1) * CELL_HEIGHT - 4,
+        );
+        writeln!(
+            out,
+            r#"<text x="{x}" y="{y}" fill="{}">{}</text>"#,
+            kind.svg(),
+            escape(grid[p])
+        )
+        .unwrap();
+    }
+
+    // Gears go last so they sit on top, each with a highlight and a hover title
+    for (p, value) in gears {
+        let (x, y) = (p.col * CELL_WIDTH, p.row * CELL_HEIGHT);
+        writeln!(
+            out,
+            r#"<g><title>{} at row {}, col {}: {value}</title><rect x="{x}" y="{y}" width="{CELL_WIDTH}" height="{CELL_HEIGHT}" fill="{}" fill-opacity="0.3"/><text x="{}" y="{}" fill="{}">{}</text></g>"#,
+            escape(grid[p]),
+            p.row + 1,
+            p.col + 1,
+            Kind::Gear.svg(),
+            x + CELL_WIDTH / 2,
+            y + CELL_HEIGHT - 4,
+            Kind::Gear.svg(),
+            escape(grid[p])
+        )
+        .unwrap();
+    }
+
+    out.push_str("</svg>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classification() {
+        let schematic: Schematic = "12*3.\n....7\n.#...".parse().unwrap();
+        let (kinds, gears) = classify(&schematic, &GearRule::puzzle());
+
+        assert_eq!(
+            kinds.row(0),
+            &[Kind::Part, Kind::Part, Kind::Gear, Kind::Part, Kind::Blank]
+        );
+        assert_eq!(kinds[Point::new(1, 4)], Kind::Rejected);
+        assert_eq!(kinds[Point::new(2, 1)], Kind::Symbol);
+        assert_eq!(gears, vec![(Point::new(0, 2), 36)]);
+    }
+
+    #[test]
+    fn ansi_runs() {
+        let schematic: Schematic = "12*3.\n7....".parse().unwrap();
+        assert_eq!(
+            ansi(&schematic, &GearRule::puzzle()),
+            "\x1b[32m12\x1b[0m\x1b[1;35m*\x1b[0m\x1b[32m3\x1b[0m\x1b[2m.\x1b[0m\n\
+             \x1b[31m7\x1b[0m\x1b[2m....\x1b[0m\n"
+        );
+    }
+
+    #[test]
+    fn svg_gears_have_titles() {
+        let schematic: Schematic = "4*5\n.&.".parse().unwrap();
+        let svg = svg(&schematic, &GearRule::puzzle());
+
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.ends_with("</svg>\n"));
+        assert!(svg.contains("<title>* at row 1, col 2: 20</title>"));
+        assert!(svg.contains(r##"<text x="5" y="12" fill="#2a2">4</text>"##));
+        // `&` isn't a gear and must be escaped
+        assert!(svg.contains(r##"<text x="15" y="28" fill="#c90">&amp;</text>"##));
+        assert_eq!(svg.matches("<title>").count(), 1);
+        assert!(!svg.contains(">.<"));
+    }
+}
@@ -0,0 +1,446 @@
+//! Part numbers, symbols and the adjacency between them.
+//!
+//! A single sweep of the grid pulls out every number and symbol. Each symbol is then linked to
+//! the numbers in its own row and the rows either side that it touches, giving a bipartite graph
+//! that both puzzle parts (and any other question about the schematic) are answered from.
+
+use crate::gear::GearRule;
+use crate::grid::{Grid, Point, RaggedRowsError};
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+
+/// A run of digits in a single row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartNumber {
+    pub value: u32,
+    pub row: usize,
+    pub col_span: Range<usize>,
+}
+
+impl PartNumber {
+    /// Returns true if the cell at `p` is one of this number's digits or next to one, diagonals
+    /// included. Every solver decides adjacency with this.
+    pub fn touches(&self, p: Point) -> bool {
+        p.row + 1 >= self.row
+            && p.row <= self.row + 1
+            && p.col + 1 >= self.col_span.start
+            && p.col <= self.col_span.end
+    }
+}
+
+/// Any cell that is neither a digit nor `.`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Symbol {
+    pub ch: char,
+    pub pos: Point,
+}
+
+pub fn is_symbol(c: char) -> bool {
+    c != '.' && !c.is_ascii_digit()
+}
+
+/// A run of digits too long to fit in a `PartNumber`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NumberTooLargeError {
+    pub row: usize,
+    pub col_span: Range<usize>,
+}
+
+impl fmt::Display for NumberTooLargeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "the number at row {}, column {} is too large",
+            self.row + 1,
+            self.col_span.start + 1
+        )
+    }
+}
+
+/// Returns the numbers in `cells`, which is row `row` of a schematic, or an error for the first
+/// one too large to hold.
+pub(crate) fn row_numbers(
+    row: usize,
+    cells: &[char],
+) -> Result<Vec<PartNumber>, NumberTooLargeError> {
+    let mut numbers = vec![];
+    let mut start = None;
+    // One past the end so a number touching the right edge is closed off
+    for col in 0..=cells.len() {
+        let is_digit = cells.get(col).is_some_and(char::is_ascii_digit);
+        match (is_digit, start) {
+            (true, None) => start = Some(col),
+            (false, Some(from)) => {
+                let digits: String = cells[from..col].iter().collect();
+                let col_span = from..col;
+                let value = digits.parse().map_err(|_| NumberTooLargeError {
+                    row,
+                    col_span: col_span.clone(),
+                })?;
+                numbers.push(PartNumber {
+                    value,
+                    row,
+                    col_span,
+                });
+                start = None;
+            }
+            _ => (),
+        }
+    }
+    Ok(numbers)
+}
+
+#[derive(Debug, Clone)]
+pub struct Schematic {
+    pub grid: Grid<char>,
+    pub numbers: Vec<PartNumber>,
+    pub symbols: Vec<Symbol>,
+    /// For each number, the indices of the symbols touching it.
+    number_symbols: Vec<Vec<usize>>,
+    /// For each symbol, the indices of the numbers touching it.
+    symbol_numbers: Vec<Vec<usize>>,
+}
+
+impl Schematic {
+    /// Panics if a number is too large for a `u32`. `stream::scan` reports that as an error
+    /// instead.
+    pub fn new(grid: Grid<char>) -> Schematic {
+        let mut numbers = vec![];
+        let mut symbols = vec![];
+        // Index of the first number in each row, and one past the last number at the end
+        let mut row_starts = vec![0];
+
+        for (row, cells) in grid.rows().enumerate() {
+            numbers.extend(row_numbers(row, cells).unwrap_or_else(|e| panic!("{e}")));
+            row_starts.push(numbers.len());
+            for (col, &c) in cells.iter().enumerate() {
+                if is_symbol(c) {
+                    symbols.push(Symbol {
+                        ch: c,
+                        pos: Point { row, col },
+                    });
+                }
+            }
+        }
+
+        let mut number_symbols = vec![vec![]; numbers.len()];
+        let mut symbol_numbers = vec![];
+        for (index, symbol) in symbols.iter().enumerate() {
+            let row = symbol.pos.row;
+            let nearby =
+                row_starts[row.saturating_sub(1)]..row_starts[(row + 2).min(grid.height())];
+            // By index rather than value, so two different numbers that happen to be equal, as in
+            // `12*12`, are both kept
+            let touching: Vec<usize> = nearby.filter(|&n| numbers[n].touches(symbol.pos)).collect();
+
+            for &number in &touching {
+                number_symbols[number].push(index);
+            }
+            symbol_numbers.push(touching);
+        }
+
+        Schematic {
+            grid,
+            numbers,
+            symbols,
+            number_symbols,
+            symbol_numbers,
+        }
+    }
+
+    /// Returns the indices of the symbols touching number `number`.
+    pub fn symbols_of(&self, number: usize) -> &[usize] {
+        &self.number_symbols[number]
+    }
+
+    /// Returns the indices of the numbers touching symbol `symbol`.
+    pub fn numbers_of(&self, symbol: usize) -> &[usize] {
+        &self.symbol_numbers[symbol]
+    }
+
+    /// Returns the numbers touching at least one symbol.
+    pub fn part_numbers(&self) -> impl Iterator<Item = &PartNumber> {
+        self.numbers
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !self.number_symbols[*i].is_empty())
+            .map(|(_, n)| n)
+    }
+
+    /// Part 1: the sum of every number touching a symbol.
+    pub fn part_sum(&self) -> u64 {
+        self.part_numbers()
+            .map(|n| u64::from(n.value))
+            .fold(0, u64::saturating_add)
+    }
+
+    /// Returns the indices of the symbols that are gears under `rule`.
+    pub fn gears<'a>(&'a self, rule: &'a GearRule) -> impl Iterator<Item = usize> + 'a {
+        (0..self.symbols.len())
+            .filter(|&i| rule.matches(self.symbols[i].ch, self.symbol_numbers[i].len()))
+    }
+
+    /// Returns the value of every gear under `rule`, in symbol order.
+    pub fn gear_values<'a>(&'a self, rule: &'a GearRule) -> impl Iterator<Item = u64> + 'a {
+        self.gears(rule).map(|i| {
+            rule.combine.apply(
+                self.symbol_numbers[i]
+                    .iter()
+                    .map(|&n| self.numbers[n].value),
+            )
+        })
+    }
+
+    /// Returns the sum of every gear value under `rule`, saturating at `u64::MAX`.
+    pub fn gear_sum(&self, rule: &GearRule) -> u64 {
+        self.gear_values(rule).fold(0, u64::saturating_add)
+    }
+
+    /// Returns the ratio of every `*` touching exactly two numbers.
+    pub fn gear_ratios(&self) -> Vec<u64> {
+        self.gear_values(&GearRule::puzzle()).collect()
+    }
+
+    /// Part 2: the sum of every gear ratio.
+    pub fn gear_ratio_sum(&self) -> u64 {
+        self.gear_sum(&GearRule::puzzle())
+    }
+
+    /// Returns the symbols with no number next to them.
+    pub fn lonely_symbols(&self) -> impl Iterator<Item = &Symbol> {
+        self.symbols
+            .iter()
+            .zip(&self.symbol_numbers)
+            .filter(|(_, numbers)| numbers.is_empty())
+            .map(|(symbol, _)| symbol)
+    }
+
+    /// Returns the numbers touching more than one symbol.
+    pub fn shared_numbers(&self) -> impl Iterator<Item = &PartNumber> {
+        self.numbers
+            .iter()
+            .zip(&self.number_symbols)
+            .filter(|(_, symbols)| symbols.len() > 1)
+            .map(|(number, _)| number)
+    }
+}
+
+impl FromStr for Schematic {
+    type Err = RaggedRowsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Schematic::new(s.parse()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PUZZLE_EXAMPLE;
+
+    const TEST_SCHEMA: &str = "..34....32
+..42*.*...
+......21..";
+
+    fn values<'a>(numbers: impl Iterator<Item = &'a PartNumber>) -> Vec<u32> {
+        numbers.map(|n| n.value).collect()
+    }
+
+    #[test]
+    fn symbol_checks() {
+        assert!(is_symbol('*'));
+        assert!(!is_symbol('.'));
+        assert!(!is_symbol('4'));
+    }
+
+    #[test]
+    fn touching() {
+        let number = PartNumber {
+            value: 123,
+            row: 1,
+            col_span: 2..5,
+        };
+        let touched: Vec<Point> = Grid::new(8, 4, ())
+            .points()
+            .filter(|&p| number.touches(p))
+            .collect();
+        assert_eq!(touched.len(), 15);
+        assert_eq!(touched[0], Point::new(0, 1));
+        assert_eq!(touched[14], Point::new(2, 5));
+        assert!(!number.touches(Point::new(3, 3)));
+        assert!(!number.touches(Point::new(1, 6)));
+    }
+
+    #[test]
+    fn oversized_numbers() {
+        assert_eq!(
+            row_numbers(2, &"1.99999999999".chars().collect::<Vec<_>>()),
+            Err(NumberTooLargeError {
+                row: 2,
+                col_span: 2..13,
+            })
+        );
+        assert_eq!(
+            NumberTooLargeError {
+                row: 2,
+                col_span: 2..13,
+            }
+            .to_string(),
+            "the number at row 3, column 3 is too large"
+        );
+    }
+
+    #[test]
+    fn extraction() {
+        let schematic: Schematic = TEST_SCHEMA.parse().unwrap();
+        assert_eq!(values(schematic.numbers.iter()), vec![34, 32, 42, 21]);
+        assert_eq!(
+            schematic.numbers[1],
+            PartNumber {
+                value: 32,
+                row: 0,
+                col_span: 8..10,
+            }
+        );
+        assert_eq!(
+            schematic.symbols,
+            vec![
+                Symbol {
+                    ch: '*',
+                    pos: Point { row: 1, col: 4 },
+                },
+                Symbol {
+                    ch: '*',
+                    pos: Point { row: 1, col: 6 },
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn adjacency() {
+        let schematic: Schematic = TEST_SCHEMA.parse().unwrap();
+        assert_eq!(schematic.numbers_of(0), &[0, 2]);
+        assert_eq!(schematic.numbers_of(1), &[3]);
+        assert_eq!(schematic.symbols_of(1), &[] as &[usize]);
+        assert_eq!(values(schematic.part_numbers()), vec![34, 42, 21]);
+    }
+
+    #[test]
+    fn puzzle_parts() {
+        let schematic: Schematic = PUZZLE_EXAMPLE.parse().unwrap();
+        assert_eq!(schematic.part_sum(), 4361);
+        assert_eq!(schematic.gear_ratios(), vec![16345, 451490]);
+        assert_eq!(schematic.gear_ratio_sum(), 467835);
+    }
+
+    #[test]
+    fn equal_valued_gear_parts() {
+        let schematic: Schematic = "12*12".parse().unwrap();
+        assert_eq!(schematic.numbers_of(0), &[0, 1]);
+        assert_eq!(schematic.gear_ratio_sum(), 144);
+
+        let stacked: Schematic = ".7.\n.*.\n..7".parse().unwrap();
+        assert_eq!(stacked.gear_ratio_sum(), 49);
+
+        // Both digits of each 55 touch the gear, still only two numbers
+        let wide: Schematic = "55.\n.*.\n55.".parse().unwrap();
+        assert_eq!(wide.numbers_of(0).len(), 2);
+        assert_eq!(wide.gear_ratio_sum(), 3025);
+
+        // Three equal numbers isn't a gear
+        let crowded: Schematic = "3.3\n.*.\n.3.".parse().unwrap();
+        assert_eq!(crowded.gear_ratio_sum(), 0);
+    }
+
+    #[test]
+    fn non_ascii_symbols() {
+        let schematic: Schematic = "§12.\n...×\n.7..".parse().unwrap();
+        assert_eq!(
+            schematic.symbols,
+            vec![
+                Symbol {
+                    ch: '§',
+                    pos: Point { row: 0, col: 0 },
+                },
+                Symbol {
+                    ch: '×',
+                    pos: Point { row: 1, col: 3 },
+                },
+            ]
+        );
+        assert_eq!(schematic.numbers[0].col_span, 1..3);
+        assert_eq!(schematic.numbers[1].col_span, 1..2);
+        assert_eq!(schematic.numbers_of(0), &[0]);
+        assert_eq!(schematic.numbers_of(1), &[0]);
+        assert_eq!(values(schematic.shared_numbers()), vec![12]);
+        assert_eq!(schematic.part_sum(), 12);
+
+        // Columns after a run of multi-byte symbols still line up with the row below
+        let schematic: Schematic = "××××.\n....5".parse().unwrap();
+        assert_eq!(schematic.part_sum(), 5);
+        let schematic: Schematic = "×××..\n....5".parse().unwrap();
+        assert_eq!(schematic.part_sum(), 0);
+    }
+
+    #[test]
+    fn gear_rules() {
+        use crate::gear::{Combine, Neighbours};
+
+        let schematic: Schematic = PUZZLE_EXAMPLE.parse().unwrap();
+        let sums = GearRule {
+            combine: Combine::Sum,
+            ..GearRule::puzzle()
+        };
+        assert_eq!(schematic.gear_sum(&sums), 467 + 35 + 755 + 598);
+
+        // Every symbol touching anything, worth its biggest neighbour
+        let any = GearRule {
+            symbols: "*#+$".chars().collect(),
+            neighbours: Neighbours::AtLeast(1),
+            combine: Combine::Max,
+        };
+        assert_eq!(schematic.gears(&any).count(), 6);
+        assert_eq!(schematic.gear_sum(&any), 467 + 633 + 617 + 592 + 664 + 755);
+
+        let lonely_stars = GearRule {
+            neighbours: Neighbours::Exactly(1),
+            ..GearRule::puzzle()
+        };
+        assert_eq!(
+            schematic.gear_values(&lonely_stars).collect::<Vec<_>>(),
+            vec![617]
+        );
+
+        let crosses = GearRule {
+            symbols: vec!['×'],
+            ..GearRule::puzzle()
+        };
+        let schematic: Schematic = "§5×5.\n....7".parse().unwrap();
+        assert_eq!(
+            schematic.gear_values(&crosses).collect::<Vec<_>>(),
+            vec![25]
+        );
+        assert_eq!(schematic.gear_ratio_sum(), 0);
+    }
+
+    #[test]
+    fn large_numbers() {
+        let schematic: Schematic = "4000000000*4000000000".parse().unwrap();
+        assert_eq!(schematic.part_sum(), 8_000_000_000);
+        assert_eq!(schematic.gear_ratio_sum(), 16_000_000_000_000_000_000);
+    }
+
+    #[test]
+    fn other_queries() {
+        let schematic: Schematic = "1.....\n.#.$..\n..22*.".parse().unwrap();
+        assert_eq!(values(schematic.shared_numbers()), vec![22]);
+        assert_eq!(schematic.lonely_symbols().count(), 0);
+
+        let schematic: Schematic = "..#..\n.....\n.5.%.".parse().unwrap();
+        let lonely: Vec<char> = schematic.lonely_symbols().map(|s| s.ch).collect();
+        assert_eq!(lonely, vec!['#', '%']);
+        assert_eq!(schematic.part_sum(), 0);
+    }
+}
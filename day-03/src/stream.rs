@@ -0,0 +1,296 @@
+//! Streaming solver for both parts.
+//!
+//! Whether a number is a part, or a symbol is a gear, only depends on the row it's in and the
+//! rows either side. Rows are read one at a time into a three-row window and the middle row is
+//! settled as soon as the row below it arrives, so memory use depends on the width of the
+//! schematic and not its height.
+
+use crate::gear::GearRule;
+use crate::grid::Point;
+use crate::schematic::{is_symbol, row_numbers, NumberTooLargeError, PartNumber};
+use std::collections::VecDeque;
+use std::io::{self, BufRead};
+
+/// Something settled while streaming, in row order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// A number touching a symbol.
+    Part(PartNumber),
+    /// A symbol that is a gear under the rule in use, and its value.
+    Gear { pos: Point, value: u64 },
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Totals {
+    pub part_sum: u64,
+    pub gear_sum: u64,
+}
+
+/// Reads a schematic from `reader`, calling `emit` for every part number and every gear under
+/// `rule` as soon as it's settled, and returns both sums.
+///
+/// Lines are read into one reused buffer rather than a new `String` each.
+/// Empty lines are skipped. Returns an `InvalidData` error if a row is a different width to the
+/// first, or holds a number too large for a `u32`.
+pub fn scan(
+    mut reader: impl BufRead,
+    rule: &GearRule,
+    mut emit: impl FnMut(Event),
+) -> io::Result<Totals> {
+    let mut totals = Totals::default();
+    let mut window: VecDeque<Vec<char>> = VecDeque::with_capacity(3);
+    // Row number of `window[0]`
+    let mut first_row = 0;
+    let mut width = None;
+    let mut text = String::new();
+    let mut line = 0;
+
+    loop {
+        text.clear();
+        if reader.read_line(&mut text)? == 0 {
+            break;
+        }
+        line += 1;
+        let text = text.trim_end_matches(['\n', '\r']);
+        if text.is_empty() {
+            continue;
+        }
+
+        let cells: Vec<char> = text.chars().collect();
+        match width {
+            None => width = Some(cells.len()),
+            Some(width) if width != cells.len() => {
+                return Err(invalid_data(format!(
+                    "line {line} is {} cells wide, expected {width}",
+                    cells.len()
+                )));
+            }
+            _ => (),
+        }
+
+        window.push_back(cells);
+        if window.len() >= 2 {
+            settle(
+                &window,
+                window.len() - 2,
+                first_row,
+                rule,
+                &mut totals,
+                &mut emit,
+            )
+            .map_err(invalid_data)?;
+        }
+        if window.len() == 3 {
+            window.pop_front();
+            first_row += 1;
+        }
+    }
+
+    if !window.is_empty() {
+        settle(
+            &window,
+            window.len() - 1,
+            first_row,
+            rule,
+            &mut totals,
+            &mut emit,
+        )
+        .map_err(invalid_data)?;
+    }
+    Ok(totals)
+}
+
+fn invalid_data(error: impl ToString) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error.to_string())
+}
+
+/// Emits the parts and gears in `window[index]`, whose neighbours are all in `window`. Fails on
+/// the first number in reach too large for a `u32`.
+fn settle(
+    window: &VecDeque<Vec<char>>,
+    index: usize,
+    first_row: usize,
+    rule: &GearRule,
+    totals: &mut Totals,
+    emit: &mut impl FnMut(Event),
+) -> Result<(), NumberTooLargeError> {
+    let row = first_row + index;
+    let cells = &window[index];
+    let nearby = index.saturating_sub(1)..(index + 2).min(window.len());
+
+    let symbols: Vec<Point> = nearby
+        .clone()
+        .flat_map(|i| {
+            let cells = window[i].iter().enumerate();
+            cells
+                .filter(|(_, &c)| is_symbol(c))
+                .map(move |(col, _)| Point::new(first_row + i, col))
+        })
+        .collect();
+    for number in row_numbers(row, cells)? {
+        if symbols.iter().any(|&p| number.touches(p)) {
+            totals.part_sum = totals.part_sum.saturating_add(u64::from(number.value));
+            emit(Event::Part(number));
+        }
+    }
+
+    let mut numbers: Option<Vec<PartNumber>> = None;
+    for (col, &ch) in cells.iter().enumerate() {
+        if !rule.symbols.contains(&ch) {
+            continue;
+        }
+        // Most rows have no gear candidates, so only look for their numbers when needed
+        if numbers.is_none() {
+            let mut found = vec![];
+            for i in nearby.clone() {
+                found.extend(row_numbers(first_row + i, &window[i])?);
+            }
+            numbers = Some(found);
+        }
+        let numbers = numbers.as_deref().unwrap_or_default();
+        let touching: Vec<u32> = numbers
+            .iter()
+            .filter(|n| n.touches(Point::new(row, col)))
+            .map(|n| n.value)
+            .collect();
+        if rule.matches(ch, touching.len()) {
+            let value = rule.combine.apply(touching.into_iter());
+            totals.gear_sum = totals.gear_sum.saturating_add(value);
+            emit(Event::Gear {
+                pos: Point::new(row, col),
+                value,
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schematic::Schematic;
+    use crate::PUZZLE_EXAMPLE;
+    use std::io::{BufReader, Read};
+
+    fn totals(input: &str) -> io::Result<Totals> {
+        scan(input.as_bytes(), &GearRule::puzzle(), |_| ())
+    }
+
+    #[test]
+    fn puzzle_example() {
+        let mut events = vec![];
+        let totals = scan(PUZZLE_EXAMPLE.as_bytes(), &GearRule::puzzle(), |e| {
+            events.push(e)
+        })
+        .unwrap();
+
+        assert_eq!(
+            totals,
+            Totals {
+                part_sum: 4361,
+                gear_sum: 467835,
+            }
+        );
+        assert_eq!(
+            events[..3],
+            [
+                Event::Part(PartNumber {
+                    value: 467,
+                    row: 0,
+                    col_span: 0..3,
+                }),
+                Event::Gear {
+                    pos: Point::new(1, 3),
+                    value: 16345,
+                },
+                Event::Part(PartNumber {
+                    value: 35,
+                    row: 2,
+                    col_span: 2..4,
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn small_schematics() {
+        assert_eq!(totals("").unwrap(), Totals::default());
+        assert_eq!(totals("12*3").unwrap().gear_sum, 36);
+        assert_eq!(totals("5\n*\n7").unwrap().gear_sum, 35);
+        let large = totals("4000000000*4000000000").unwrap();
+        assert_eq!(large.part_sum, 8_000_000_000);
+        assert_eq!(large.gear_sum, 16_000_000_000_000_000_000);
+        assert_eq!(totals("5.\r\n\r\n.#\r\n").unwrap().part_sum, 5);
+        assert_eq!(
+            totals("..\n...").unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+
+        let too_large = totals("1...........\n99999999999*").unwrap_err();
+        assert_eq!(too_large.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            too_large.to_string(),
+            "the number at row 2, column 1 is too large"
+        );
+    }
+
+    #[test]
+    fn matches_the_full_grid() {
+        let input = include_str!("bin/input.txt");
+        let schematic: Schematic = input.parse().unwrap();
+        assert_eq!(
+            totals(input).unwrap(),
+            Totals {
+                part_sum: schematic.part_sum(),
+                gear_sum: schematic.gear_ratio_sum(),
+            }
+        );
+    }
+
+    /// Produces `text` over and over without ever holding more than one copy of it.
+    struct Repeat {
+        text: Vec<u8>,
+        pos: usize,
+        remaining: usize,
+    }
+
+    impl Read for Repeat {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.remaining == 0 {
+                return Ok(0);
+            }
+            let n = buf.len().min(self.text.len() - self.pos);
+            buf[..n].copy_from_slice(&self.text[self.pos..self.pos + n]);
+            self.pos += n;
+            if self.pos == self.text.len() {
+                self.pos = 0;
+                self.remaining -= 1;
+            }
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn million_rows() {
+        // The example's first and last rows have no symbols, so copies don't interact
+        let copies = 100_000;
+        let reader = BufReader::new(Repeat {
+            text: format!("{PUZZLE_EXAMPLE}\n").into_bytes(),
+            pos: 0,
+            remaining: copies,
+        });
+
+        let mut gears = 0;
+        let totals = scan(reader, &GearRule::puzzle(), |e| {
+            if let Event::Gear { pos, .. } = e {
+                assert_eq!(pos.row % 10, if gears % 2 == 0 { 1 } else { 8 });
+                gears += 1;
+            }
+        })
+        .unwrap();
+
+        assert_eq!(gears, 2 * copies);
+        assert_eq!(totals.part_sum, 4361 * copies as u64);
+        assert_eq!(totals.gear_sum, 467835 * copies as u64);
+    }
+}
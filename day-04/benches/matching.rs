@@ -0,0 +1,92 @@
+//! Counting matches on generated cards: `Vec::contains` for every number (the original
+//! approach) versus `day_04::count_common`.
+//!
+//! Two decks are generated, one whose numbers all fit the `u128` bitset and one spread over a
+//! wider range that takes the sorted merge fallback. Set `AOC_BENCH_CARDS` to change the number
+//! of cards per deck (1000 by default) and `AOC_BENCH_ITERS` the number of runs (20 by default).
+//!
+//! Run with `cargo bench --bench matching`.
+
+use day_04::Card;
+use std::time::Instant;
+
+/// xorshift32, plenty for shuffling test data.
+struct Rng(u32);
+
+impl Rng {
+    fn next(&mut self) -> u32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        self.0
+    }
+
+    /// Returns `count` distinct numbers below `range`.
+    fn distinct(&mut self, count: usize, range: u32) -> Vec<u32> {
+        let mut pool: Vec<u32> = (0..range).collect();
+        for i in 0..count {
+            let j = i + (self.next() as usize) % (pool.len() - i);
+            pool.swap(i, j);
+        }
+        pool.truncate(count);
+        pool
+    }
+}
+
+fn deck(rng: &mut Rng, cards: usize, winning: usize, have: usize, range: u32) -> Vec<Card> {
+    (1..=cards as u32)
+        .map(|id| Card {
+            id,
+            winning: rng.distinct(winning, range),
+            have: rng.distinct(have, range),
+        })
+        .collect()
+}
+
+fn contains(card: &Card) -> u32 {
+    card.have
+        .iter()
+        .filter(|n| card.winning.contains(n))
+        .count() as u32
+}
+
+/// Returns the mean seconds per run of `f` over `deck`, checking every run gives `expected`.
+fn time(iters: u32, deck: &[Card], expected: u32, f: impl Fn(&Card) -> u32) -> f64 {
+    let start = Instant::now();
+    for _ in 0..iters {
+        let total: u32 = std::hint::black_box(deck).iter().map(&f).sum();
+        assert_eq!(total, expected);
+    }
+    start.elapsed().as_secs_f64() / iters as f64
+}
+
+fn main() {
+    let env = |name: &str, default| {
+        std::env::var(name)
+            .ok()
+            .map(|a| {
+                a.parse()
+                    .unwrap_or_else(|_| panic!("Expected a number for {name}"))
+            })
+            .unwrap_or(default)
+    };
+    let cards = env("AOC_BENCH_CARDS", 1000);
+    let iters = env("AOC_BENCH_ITERS", 20) as u32;
+
+    let mut rng = Rng(0x2023_0004);
+    let decks = [
+        ("0..128", deck(&mut rng, cards, 60, 100, 128)),
+        ("0..10000", deck(&mut rng, cards, 200, 500, 10_000)),
+    ];
+
+    for (name, deck) in &decks {
+        let expected = deck.iter().map(contains).sum();
+        let naive = time(iters, deck, expected, contains);
+        let fast = time(iters, deck, expected, Card::matches);
+
+        println!("{name}:");
+        println!("  contains: {:>10.1} us/run", naive * 1e6);
+        println!("  matches:  {:>10.1} us/run", fast * 1e6);
+        println!("  speedup:  {:>10.1}x", naive / fast);
+    }
+}
@@ -1,47 +1,35 @@
-/// Solution for Advent Of Code 2023, Day 4, Part 1.
-/// Author: Tyler Weir
-///
-/// ## Prompt
-///
-/// Given a input lines: "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
-///                               [winning nums]        [our nums]
-///
-/// Per line, one matching num yields a score of 1, subsequent matches double the score.
-/// Find the sum of each line's score
-
-fn parse_line(input: &str) -> u32 {
-    let nums = input.split_whitespace().skip(2);
-    let winning_nums: Vec<u32> = nums
-        .clone()
-        .take_while(|a| !a.contains("|"))
-        .map(|a| a.parse().expect("Failed to parse number"))
-        .collect();
-
-    // Fold expression sets score to 1 on the first winning number match, doubles on subsequent
-    // matches
-    let scorer = |acc, x| {
-        if winning_nums.contains(&x) {
-            match acc {
-                0 => 1,
-                _ => acc * 2,
-            }
-        } else {
-            acc
-        }
-    };
-
-    nums.skip_while(|a| !a.contains("|"))
-        .skip(1)
-        .map(|a| a.parse().expect("Failed to parse number"))
-        .fold(0, scorer)
-}
-
-fn solve(input: &str) -> u32 {
-    input.lines().map(parse_line).sum()
+//! Solution for Advent Of Code 2023, Day 4, Part 1.
+//! Author: Tyler Weir
+//!
+//! ## Prompt
+//!
+//! Given a input lines: "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+//!                               [winning nums]        [our nums]
+//!
+//! Per line, one matching num yields a score of 1, subsequent matches double the score.
+//! Find the sum of each line's score
+
+use day_04::scoring::{self, Doubling, ScoringRule};
+
+fn solve(input: &str, rule: &dyn ScoringRule) -> u64 {
+    rule.score(&day_04::match_counts(input).unwrap_or_else(|e| panic!("{e}")))
 }
 
+/// Usage: `part1 [--rule <name>]`, where the rule is any of `scoring::parse_rule`'s names and
+/// defaults to the puzzle's `doubling`.
 fn main() {
-    println!("{}", solve(include_str!("./input.txt")));
+    let mut rule: Box<dyn ScoringRule> = Box::new(Doubling);
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--rule" => {
+                let name = args.next().expect("Missing value for --rule");
+                rule = scoring::parse_rule(&name).unwrap_or_else(|e| panic!("{e}"));
+            }
+            _ => panic!("Unknown argument: {arg}"),
+        }
+    }
+    println!("{}", solve(include_str!("./input.txt"), rule.as_ref()));
 }
 
 #[cfg(test)]
@@ -57,6 +45,12 @@ Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
 
     #[test]
     fn example_puzzle() {
-        assert_eq!(solve(SAMPLE), 13);
+        assert_eq!(solve(SAMPLE, &Doubling), 13);
+    }
+
+    #[test]
+    fn other_rules() {
+        assert_eq!(solve(SAMPLE, &scoring::Linear), 9);
+        assert_eq!(solve(SAMPLE, &scoring::Fibonacci), 10);
     }
 }
@@ -1,76 +1,46 @@
-/// Solution for Advent Of Code 2023, Day 4, Part 2.
-/// Author: Tyler Weir
-///
-/// ## Prompt
-///
-/// Given a input lines: "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
-///                               [winning nums]        [our nums]
-///
-/// `n` winning numbers causes copies to be made of the next n cards. After evaluating the original
-/// cards and all the copies, how many cards do you have?
-///
-use std::collections::HashMap;
+//! Solution for Advent Of Code 2023, Day 4, Part 2.
+//! Author: Tyler Weir
+//!
+//! ## Prompt
+//!
+//! Given a input lines: "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+//!                               [winning nums]        [our nums]
+//!
+//! `n` winning numbers causes copies to be made of the next n cards. After evaluating the original
+//! cards and all the copies, how many cards do you have?
 
-/// Returns the number of winning numbers for a card string
-fn parse_line(input: &str) -> u32 {
-    let nums = input.split_whitespace().skip(2);
-    let winning_nums: Vec<u32> = nums
-        .clone()
-        .take_while(|a| !a.contains("|"))
-        .map(|a| a.parse().expect("Failed to parse number"))
-        .collect();
+use day_04::scoring::{self, Copies, ScoringRule};
 
-    // Fold expression, counts the number of matches
-    let scorer = |acc, x| {
-        if winning_nums.contains(&x) {
-            acc + 1
-        } else {
-            acc
-        }
-    };
-
-    nums.skip_while(|a| !a.contains("|"))
-        .skip(1)
-        .map(|a| a.parse().expect("Failed to parse number"))
-        .fold(0, scorer)
+fn solve(input: &str, rule: &dyn ScoringRule) -> u64 {
+    rule.score(&day_04::match_counts(input).unwrap_or_else(|e| panic!("{e}")))
 }
 
-fn solver_impl(input: u32, matches: &HashMap<u32, u32>, is_original: bool) -> u32 {
-    // TODO(tyler) can this be memoized, I think yes for the copies...
-    let num_matches = match matches.get(&input) {
-        Some(a) => a,
-        None => return 0,
-    };
-    let mut sum = 1;
-
-    if is_original {
-        sum += solver_impl(input + 1, matches, true);
+/// Usage: `part2 [--rule <name>] [--counts]`, where the rule is any of `scoring::parse_rule`'s
+/// names and defaults to the puzzle's `copies`. With `--counts` every card's value under the
+/// rule is listed before the total.
+fn main() {
+    let mut rule: Box<dyn ScoringRule> = Box::new(Copies);
+    let mut counts = false;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--rule" => {
+                let name = args.next().expect("Missing value for --rule");
+                rule = scoring::parse_rule(&name).unwrap_or_else(|e| panic!("{e}"));
+            }
+            "--counts" => counts = true,
+            _ => panic!("Unknown argument: {arg}"),
+        }
     }
 
-    let lower = input + 1;
-    let upper = input + 1 + num_matches;
-    for i in lower..upper {
-        sum += solver_impl(i, matches, false);
+    let input = include_str!("./input.txt");
+    if counts {
+        let matches = day_04::match_counts(input).unwrap_or_else(|e| panic!("{e}"));
+        for (card, count) in rule.per_card(&matches).iter().enumerate() {
+            println!("Card {}: {count}", card + 1);
+        }
     }
-
-    sum
-}
-
-fn solve(input: &str) -> u32 {
-    // Find the number of matches for each card
-    // TODO can this be lazily evaluated?
-    let matches: HashMap<u32, u32> = input
-        .lines()
-        .map(parse_line)
-        .enumerate()
-        .map(|(line, val)| (line as u32 + 1, val))
-        .collect();
-
-    solver_impl(1, &matches, true)
-}
-
-fn main() {
-    println!("{}", solve(include_str!("./input.txt")));
+    println!("{}", solve(input, rule.as_ref()));
 }
 
 #[cfg(test)]
@@ -86,11 +56,17 @@ Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
 
     #[test]
     fn example_puzzle() {
-        assert_eq!(solve(SAMPLE), 30);
+        assert_eq!(solve(SAMPLE, &Copies), 30);
+    }
+
+    #[test]
+    fn other_rules() {
+        assert_eq!(solve(SAMPLE, &scoring::WrappingCopies), 30);
+        assert_eq!(solve(SAMPLE, &scoring::CappedCopies { cap: 5 }), 18);
     }
 
     #[test]
     fn full_puzzle() {
-        assert_eq!(solve(include_str!("./input.txt")), 5095824);
+        assert_eq!(solve(include_str!("./input.txt"), &Copies), 5095824);
     }
 }
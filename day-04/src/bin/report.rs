@@ -0,0 +1,47 @@
+//! Copy provenance report for a day 4 card file.
+//!
+//! Usage: `report [--json | --dot] [--top <n>] [--input <file>]`
+//!
+//! Shows, for every card, how many copies it ended up with, which earlier cards won them, and
+//! the longest chain of wins that reached it, followed by the cards that handed out the most
+//! copies. `--dot` draws the hand-offs as a Graphviz graph instead.
+
+use day_04::provenance::Provenance;
+
+fn main() {
+    let mut json = false;
+    let mut dot = false;
+    let mut top = 5;
+    let mut path = None;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .unwrap_or_else(|| panic!("Missing value for {arg}"))
+        };
+        match arg.as_str() {
+            "--json" => json = true,
+            "--dot" => dot = true,
+            "--top" => top = value().parse().expect("Expected a number of cards"),
+            "--input" => path = Some(value()),
+            _ => panic!("Unknown argument: {arg}"),
+        }
+    }
+    assert!(!(json && dot), "Pick one of --json and --dot");
+
+    let input = match path {
+        Some(path) => std::fs::read_to_string(path).expect("Failed to read input"),
+        None => include_str!("./input.txt").to_string(),
+    };
+    let matches = day_04::match_counts(&input).unwrap_or_else(|e| panic!("{e}"));
+    let report = Provenance::new(&matches, top);
+
+    if json {
+        println!("{:#}", report.to_json());
+    } else if dot {
+        print!("{}", report.to_dot());
+    } else {
+        print!("{}", report.to_table());
+    }
+}
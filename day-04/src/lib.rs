@@ -0,0 +1,338 @@
+//! Shared scratchcard model for Advent of Code 2023, day 4.
+
+pub mod provenance;
+pub mod scoring;
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+/// A scratchcard, `Card <id>: <winning numbers> | <numbers you have>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Card {
+    pub id: u32,
+    pub winning: Vec<u32>,
+    pub have: Vec<u32>,
+}
+
+impl Card {
+    /// Returns how many of the numbers you have are winning numbers.
+    pub fn matches(&self) -> u32 {
+        count_common(&self.winning, &self.have)
+    }
+}
+
+/// Numbers below this fit in a `u128` bitset.
+const BITSET_RANGE: u32 = 128;
+
+/// Returns `numbers` as a bitset, or `None` if any are too large.
+fn bitset(numbers: &[u32]) -> Option<u128> {
+    numbers
+        .iter()
+        .try_fold(0, |bits, &n| (n < BITSET_RANGE).then(|| bits | 1 << n))
+}
+
+/// Counts the common numbers of two lists by sorting both and walking them side by side.
+fn sorted_merge(a: &[u32], b: &[u32]) -> u32 {
+    let (mut a, mut b) = (a.to_vec(), b.to_vec());
+    a.sort_unstable();
+    b.sort_unstable();
+
+    let (mut i, mut j, mut common) = (0, 0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            Ordering::Less => i += 1,
+            Ordering::Greater => j += 1,
+            Ordering::Equal => {
+                common += 1;
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    common
+}
+
+/// Returns how many numbers appear in both `a` and `b`, neither of which may hold repeats (as
+/// `Card::from_str` ensures).
+///
+/// When every number is below 128 this is the popcount of two `u128` bitsets ANDed together,
+/// otherwise it falls back to sorting both lists and merging them, O(n log n).
+pub fn count_common(a: &[u32], b: &[u32]) -> u32 {
+    match (bitset(a), bitset(b)) {
+        (Some(a), Some(b)) => (a & b).count_ones(),
+        _ => sorted_merge(a, b),
+    }
+}
+
+/// Which list of numbers on a card.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Winning,
+    Have,
+}
+
+impl fmt::Display for Side {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Side::Winning => write!(f, "winning numbers"),
+            Side::Have => write!(f, "numbers you have"),
+        }
+    }
+}
+
+/// Why a line isn't a valid card.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseCardError {
+    /// The line doesn't start with `Card`.
+    MissingPrefix,
+    /// The text between `Card` and `:` isn't a number.
+    BadId(String),
+    /// No `:` after the card number.
+    MissingColon,
+    /// No `|` between the winning numbers and the numbers you have.
+    MissingSeparator,
+    /// Something in a list that isn't a number.
+    BadNumber(String),
+    /// The same number twice in one list.
+    DuplicateNumber { side: Side, number: u32 },
+    /// Cards must be numbered 1, 2, 3… in order with none missing.
+    UnexpectedId { expected: u32, found: u32 },
+}
+
+impl fmt::Display for ParseCardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseCardError::MissingPrefix => write!(f, "expected `Card`"),
+            ParseCardError::BadId(id) => write!(f, "expected a card number, got {id:?}"),
+            ParseCardError::MissingColon => write!(f, "expected `:` after the card number"),
+            ParseCardError::MissingSeparator => write!(f, "expected `|` between the two lists"),
+            ParseCardError::BadNumber(text) => write!(f, "expected a number, got {text:?}"),
+            ParseCardError::DuplicateNumber { side, number } => {
+                write!(f, "{number} appears twice in the {side}")
+            }
+            ParseCardError::UnexpectedId { expected, found } => {
+                write!(f, "expected card {expected}, found card {found}")
+            }
+        }
+    }
+}
+
+/// Parses a whitespace separated list of numbers, rejecting repeats.
+fn parse_numbers(text: &str, side: Side) -> Result<Vec<u32>, ParseCardError> {
+    let mut numbers: Vec<u32> = vec![];
+    for token in text.split_whitespace() {
+        let number = token
+            .parse()
+            .map_err(|_| ParseCardError::BadNumber(token.to_string()))?;
+        if numbers.contains(&number) {
+            return Err(ParseCardError::DuplicateNumber { side, number });
+        }
+        numbers.push(number);
+    }
+    Ok(numbers)
+}
+
+impl FromStr for Card {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rest = s
+            .trim_start()
+            .strip_prefix("Card")
+            .ok_or(ParseCardError::MissingPrefix)?;
+        let (id, body) = rest.split_once(':').ok_or(ParseCardError::MissingColon)?;
+        let id = id
+            .trim()
+            .parse()
+            .map_err(|_| ParseCardError::BadId(id.trim().to_string()))?;
+        let (winning, have) = body
+            .split_once('|')
+            .ok_or(ParseCardError::MissingSeparator)?;
+
+        Ok(Card {
+            id,
+            winning: parse_numbers(winning, Side::Winning)?,
+            have: parse_numbers(have, Side::Have)?,
+        })
+    }
+}
+
+/// A line of a card file that failed to parse.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseInputError {
+    /// One based line number.
+    pub line: usize,
+    pub text: String,
+    pub error: ParseCardError,
+}
+
+impl fmt::Display for ParseInputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}\n  {}", self.line, self.error, self.text)
+    }
+}
+
+/// Parses every non-empty line of a card file, checking the cards are numbered from 1 with no
+/// gaps.
+pub fn parse_cards(input: &str) -> Result<Vec<Card>, ParseInputError> {
+    let mut cards: Vec<Card> = vec![];
+    for (number, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let error = |error| ParseInputError {
+            line: number + 1,
+            text: line.to_string(),
+            error,
+        };
+
+        let card: Card = line.parse().map_err(error)?;
+        let expected = cards.len() as u32 + 1;
+        if card.id != expected {
+            return Err(error(ParseCardError::UnexpectedId {
+                expected,
+                found: card.id,
+            }));
+        }
+        cards.push(card);
+    }
+    Ok(cards)
+}
+
+/// Parses a card file and returns the number of matches on each card, in order.
+pub fn match_counts(input: &str) -> Result<Vec<u32>, ParseInputError> {
+    Ok(parse_cards(input)?.iter().map(Card::matches).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
+
+    #[test]
+    fn card_from_str() {
+        assert_eq!(
+            "Card  12: 41 48 | 83  6 41".parse(),
+            Ok(Card {
+                id: 12,
+                winning: vec![41, 48],
+                have: vec![83, 6, 41],
+            })
+        );
+        assert_eq!(
+            "Card 3: | ".parse(),
+            Ok(Card {
+                id: 3,
+                winning: vec![],
+                have: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn bad_cards() {
+        let error = |s: &str| s.parse::<Card>().unwrap_err();
+        assert_eq!(error("Cart 1: 1 | 2"), ParseCardError::MissingPrefix);
+        assert_eq!(
+            error("Card x: 1 | 2"),
+            ParseCardError::BadId("x".to_string())
+        );
+        assert_eq!(error("Card 1 1 | 2"), ParseCardError::MissingColon);
+        assert_eq!(error("Card 1: 1 2 3"), ParseCardError::MissingSeparator);
+        assert_eq!(
+            error("Card 1: 1 | 2 b"),
+            ParseCardError::BadNumber("b".to_string())
+        );
+        assert_eq!(
+            error("Card 1: 5 7 5 | 2"),
+            ParseCardError::DuplicateNumber {
+                side: Side::Winning,
+                number: 5,
+            }
+        );
+        assert_eq!(
+            error("Card 1: 5 | 2 2").to_string(),
+            "2 appears twice in the numbers you have"
+        );
+    }
+
+    #[test]
+    fn matches() {
+        assert_eq!(match_counts(SAMPLE).unwrap(), vec![4, 2, 2, 1, 0, 0]);
+    }
+
+    #[test]
+    fn common_numbers() {
+        assert_eq!(bitset(&[0, 5, 127]), Some(1 | 1 << 5 | 1 << 127));
+        assert_eq!(bitset(&[0, 128]), None);
+
+        assert_eq!(count_common(&[41, 48, 83, 86, 17], &[83, 86, 6, 31, 17]), 3);
+        assert_eq!(count_common(&[], &[1, 2]), 0);
+        // Anything over 127 takes the sorted merge
+        assert_eq!(count_common(&[1000, 5, 300], &[300, 7, 5, 9999]), 2);
+        assert_eq!(sorted_merge(&[3, 1, 2], &[2, 3, 4]), 2);
+        assert_eq!(sorted_merge(&[500, 2, 9], &[1, 9, 400, 500, 600]), 2);
+    }
+
+    #[test]
+    fn bitset_agrees_with_merge() {
+        // Every small list from a cheap deterministic sequence, with and without the fast path
+        let mut state = 1u32;
+        let mut next = || {
+            state = state.wrapping_mul(1103515245).wrapping_add(12345);
+            (state >> 16) % 128
+        };
+        for len in 0..40 {
+            let mut a: Vec<u32> = (0..len).map(|_| next()).collect();
+            let mut b: Vec<u32> = (0..len * 2).map(|_| next()).collect();
+            a.sort_unstable();
+            a.dedup();
+            b.sort_unstable();
+            b.dedup();
+
+            let naive = b.iter().filter(|n| a.contains(n)).count() as u32;
+            assert_eq!(count_common(&a, &b), naive);
+            assert_eq!(sorted_merge(&a, &b), naive);
+        }
+    }
+
+    #[test]
+    fn card_order() {
+        assert_eq!(
+            parse_cards("\nCard 1: 1 | 2\n\nCard 2: | \n")
+                .unwrap()
+                .len(),
+            2
+        );
+
+        let missing = parse_cards("Card 1: 1 | 2\nCard 3: 1 | 2").unwrap_err();
+        assert_eq!(missing.line, 2);
+        assert_eq!(
+            missing.error,
+            ParseCardError::UnexpectedId {
+                expected: 2,
+                found: 3,
+            }
+        );
+
+        let swapped = parse_cards("Card 2: 1 | 2\nCard 1: 1 | 2").unwrap_err();
+        assert_eq!(
+            swapped.to_string(),
+            "line 1: expected card 1, found card 2\n  Card 2: 1 | 2"
+        );
+    }
+
+    #[test]
+    fn missing_separator_is_an_error() {
+        let error = parse_cards("Card 1: 1 2 | 3\nCard 2: 41 48 83").unwrap_err();
+        assert_eq!(error.line, 2);
+        assert_eq!(error.error, ParseCardError::MissingSeparator);
+    }
+}
@@ -0,0 +1,299 @@
+//! Where every copy of every card came from under the part 2 rules.
+//!
+//! A card with `n` matches hands one copy to each of the next `n` cards for every copy of itself
+//! it holds. Counting the copies in one pass, as `scoring::copy_counts` does, and recording
+//! every hand-off along the way gives the copies each card received from each earlier card, the
+//! longest chain of wins that reached it and how much each card added to the total.
+
+use serde_json::json;
+use std::fmt::Write;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CardProvenance {
+    pub card: u32,
+    /// Copies held at the end, the original included.
+    pub copies: u64,
+    /// `(card, copies)` for every earlier card that won copies of this one, in card order.
+    pub sources: Vec<(u32, u64)>,
+    /// The longest run of cards, each won by the one before, from an original to this card.
+    /// Ties go to the earliest card.
+    pub chain: Vec<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Provenance {
+    pub cards: Vec<CardProvenance>,
+    /// `(card, copies)` for the cards that handed out the most copies, most first. The total is
+    /// the number of cards plus every card's hand-outs.
+    pub top_contributors: Vec<(u32, u64)>,
+}
+
+impl Provenance {
+    /// Traces the copies of every card given each card's match count, keeping the `top` cards
+    /// that handed out the most copies.
+    pub fn new(matches: &[u32], top: usize) -> Provenance {
+        let mut counts: Vec<u64> = vec![1; matches.len()];
+        let mut sources: Vec<Vec<(u32, u64)>> = vec![vec![]; matches.len()];
+        // Card before each card in its longest chain, and that chain's length
+        let mut chains: Vec<(Option<usize>, usize)> = vec![(None, 1); matches.len()];
+        let mut handed_out = vec![0u64; matches.len()];
+
+        for (i, &won) in matches.iter().enumerate() {
+            let end = (i + 1 + won as usize).min(matches.len());
+            // Every earlier card has already handed over its copies, so this count is final
+            let copies = counts[i];
+            for j in i + 1..end {
+                counts[j] = counts[j].saturating_add(copies);
+                sources[j].push((i as u32 + 1, copies));
+                handed_out[i] = handed_out[i].saturating_add(copies);
+                // Strictly longer only, so the earliest source wins ties
+                if chains[i].1 + 1 > chains[j].1 {
+                    chains[j] = (Some(i), chains[i].1 + 1);
+                }
+            }
+        }
+
+        let cards = sources
+            .into_iter()
+            .enumerate()
+            .map(|(i, sources)| {
+                let mut chain = vec![i as u32 + 1];
+                let mut at = i;
+                while let (Some(previous), _) = chains[at] {
+                    chain.push(previous as u32 + 1);
+                    at = previous;
+                }
+                chain.reverse();
+
+                CardProvenance {
+                    card: i as u32 + 1,
+                    copies: counts[i],
+                    sources,
+                    chain,
+                }
+            })
+            .collect();
+
+        let mut top_contributors: Vec<(u32, u64)> = handed_out
+            .iter()
+            .enumerate()
+            .filter(|(_, &n)| n > 0)
+            .map(|(i, &n)| (i as u32 + 1, n))
+            .collect();
+        // Most copies first, lowest card breaks ties
+        top_contributors.sort_by_key(|&(card, n)| (std::cmp::Reverse(n), card));
+        top_contributors.truncate(top);
+
+        Provenance {
+            cards,
+            top_contributors,
+        }
+    }
+
+    /// The total number of cards held at the end, the part 2 answer.
+    pub fn total(&self) -> u64 {
+        self.cards
+            .iter()
+            .map(|c| c.copies)
+            .fold(0, u64::saturating_add)
+    }
+
+    /// Renders the report as plain text tables.
+    pub fn to_table(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "total cards: {}", self.total()).unwrap();
+
+        writeln!(
+            out,
+            "\n{:<6}{:<10}{:<7}sources (card×copies)",
+            "card", "copies", "chain"
+        )
+        .unwrap();
+        for card in &self.cards {
+            let sources: Vec<String> = card
+                .sources
+                .iter()
+                .map(|(source, copies)| format!("{source}×{copies}"))
+                .collect();
+            let row = format!(
+                "{:<6}{:<10}{:<7}{}",
+                card.card,
+                card.copies,
+                card.chain.len(),
+                sources.join(" ")
+            );
+            writeln!(out, "{}", row.trim_end()).unwrap();
+        }
+
+        writeln!(out, "\ntop contributors\n{:<6}copies handed out", "card").unwrap();
+        for (card, copies) in &self.top_contributors {
+            writeln!(out, "{card:<6}{copies}").unwrap();
+        }
+
+        out
+    }
+
+    /// Renders the report as a JSON object.
+    pub fn to_json(&self) -> serde_json::Value {
+        let cards: Vec<serde_json::Value> = self
+            .cards
+            .iter()
+            .map(|card| {
+                let sources: Vec<serde_json::Value> = card
+                    .sources
+                    .iter()
+                    .map(|(source, copies)| json!({ "card": source, "copies": copies }))
+                    .collect();
+                json!({
+                    "card": card.card,
+                    "copies": card.copies,
+                    "sources": sources,
+                    "chain": card.chain,
+                })
+            })
+            .collect();
+        let top: Vec<serde_json::Value> = self
+            .top_contributors
+            .iter()
+            .map(|(card, copies)| json!({ "card": card, "handed_out": copies }))
+            .collect();
+
+        json!({
+            "total": self.total(),
+            "cards": cards,
+            "top_contributors": top,
+        })
+    }
+
+    /// Renders the report as a Graphviz digraph, one node per card and one edge per hand-off
+    /// labelled with the copies handed over.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph cards {\n    rankdir=LR;\n");
+        for card in &self.cards {
+            writeln!(
+                out,
+                "    card{} [label=\"Card {}\\n{} copies\"];",
+                card.card, card.card, card.copies
+            )
+            .unwrap();
+        }
+        for card in &self.cards {
+            for (source, copies) in &card.sources {
+                writeln!(
+                    out,
+                    "    card{source} -> card{} [label=\"{copies}\"];",
+                    card.card
+                )
+                .unwrap();
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scoring::copy_counts;
+
+    /// Match counts of the puzzle's example cards.
+    const SAMPLE: [u32; 6] = [4, 2, 2, 1, 0, 0];
+
+    #[test]
+    fn sample_provenance() {
+        let report = Provenance::new(&SAMPLE, 3);
+        assert_eq!(report.total(), 30);
+
+        assert_eq!(
+            report.cards[4],
+            CardProvenance {
+                card: 5,
+                copies: 14,
+                sources: vec![(1, 1), (3, 4), (4, 8)],
+                chain: vec![1, 2, 3, 4, 5],
+            }
+        );
+        assert_eq!(report.cards[0].sources, vec![]);
+        assert_eq!(report.cards[0].chain, vec![1]);
+        // Nothing wins card 6
+        assert_eq!(report.cards[5].copies, 1);
+        assert_eq!(report.cards[5].chain, vec![6]);
+
+        assert_eq!(report.top_contributors, vec![(3, 8), (4, 8), (1, 4)]);
+        let handed_out: u64 = Provenance::new(&SAMPLE, usize::MAX)
+            .top_contributors
+            .iter()
+            .map(|(_, n)| n)
+            .sum();
+        assert_eq!(handed_out + SAMPLE.len() as u64, 30);
+    }
+
+    #[test]
+    fn matches_copy_counts() {
+        let copies = |matches: &[u32]| -> Vec<u64> {
+            Provenance::new(matches, 0)
+                .cards
+                .iter()
+                .map(|c| c.copies)
+                .collect()
+        };
+        assert_eq!(copies(&SAMPLE), copy_counts(&SAMPLE));
+        assert_eq!(copies(&[0, 3, 5]), copy_counts(&[0, 3, 5]));
+
+        // Counts and hand-outs saturate rather than overflowing
+        let huge = Provenance::new(&[2; 100], 1);
+        assert_eq!(huge.cards[99].copies, u64::MAX);
+        assert_eq!(huge.total(), u64::MAX);
+        assert_eq!(huge.top_contributors[0].1, u64::MAX);
+    }
+
+    #[test]
+    fn table() {
+        let table = Provenance::new(&[1, 0], 5).to_table();
+        assert_eq!(
+            table,
+            "total cards: 3
+
+card  copies    chain  sources (card×copies)
+1     1         1
+2     2         2      1×1
+
+top contributors
+card  copies handed out
+1     1
+"
+        );
+    }
+
+    #[test]
+    fn json() {
+        let json = Provenance::new(&[1, 0], 5).to_json();
+        assert_eq!(
+            json,
+            json!({
+                "total": 3,
+                "cards": [
+                    { "card": 1, "copies": 1, "sources": [], "chain": [1] },
+                    {
+                        "card": 2,
+                        "copies": 2,
+                        "sources": [{ "card": 1, "copies": 1 }],
+                        "chain": [1, 2],
+                    },
+                ],
+                "top_contributors": [{ "card": 1, "handed_out": 1 }],
+            })
+        );
+    }
+
+    #[test]
+    fn dot() {
+        let dot = Provenance::new(&SAMPLE, 5).to_dot();
+        assert!(dot.starts_with("digraph cards {\n"));
+        assert!(dot.ends_with("}\n"));
+        assert!(dot.contains("    card5 [label=\"Card 5\\n14 copies\"];\n"));
+        assert!(dot.contains("    card4 -> card5 [label=\"8\"];\n"));
+        assert_eq!(dot.matches("->").count(), 9);
+    }
+}
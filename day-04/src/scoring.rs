@@ -0,0 +1,220 @@
+//! Ways of turning each card's match count into a score for the whole pile.
+//!
+//! Part 1 scores cards on their own, part 2 has cards win copies of the cards after them. Both,
+//! and a few variations on each, are `ScoringRule`s over the same list of match counts, so one
+//! parsed pile can be scored under any of them.
+
+/// A way of scoring a pile of scratchcards.
+pub trait ScoringRule {
+    /// Returns what each card is worth, given the number of matches on each card in order. For
+    /// points rules that's the card's points, for copy rules how many of it you end up holding.
+    fn per_card(&self, matches: &[u32]) -> Vec<u64>;
+
+    /// Returns the score of the whole pile, saturating at `u64::MAX` like the per card values.
+    fn score(&self, matches: &[u32]) -> u64 {
+        self.per_card(matches)
+            .into_iter()
+            .fold(0, u64::saturating_add)
+    }
+}
+
+/// Part 1: one point for the first match, doubled for every match after that.
+pub struct Doubling;
+
+/// One point per match.
+pub struct Linear;
+
+/// 1, 2, 3, 5, 8… points for one, two, three, four, five… matches.
+pub struct Fibonacci;
+
+/// Part 2: a card with `n` matches wins a copy of each of the next `n` cards. Wins past the last
+/// card are lost.
+pub struct Copies;
+
+/// As `Copies`, but wins past the last card wrap round to the first.
+///
+/// Cards are still scratched once each in order, so copies that wrap round to a card that has
+/// already been scratched are held but win nothing themselves. Without that a single win on the
+/// last card would loop forever.
+pub struct WrappingCopies;
+
+/// As `Copies`, but nobody can hold more than `cap` of any one card.
+pub struct CappedCopies {
+    pub cap: u64,
+}
+
+/// Scores each card on its own match count.
+fn points(matches: &[u32], rule: impl Fn(u32) -> u64) -> Vec<u64> {
+    matches.iter().map(|&m| rule(m)).collect()
+}
+
+impl ScoringRule for Doubling {
+    fn per_card(&self, matches: &[u32]) -> Vec<u64> {
+        points(matches, |m| match m {
+            0 => 0,
+            // Saturates rather than overflowing on cards with more than 64 matches
+            m => 1u64.checked_shl(m - 1).unwrap_or(u64::MAX),
+        })
+    }
+}
+
+impl ScoringRule for Linear {
+    fn per_card(&self, matches: &[u32]) -> Vec<u64> {
+        points(matches, u64::from)
+    }
+}
+
+impl ScoringRule for Fibonacci {
+    fn per_card(&self, matches: &[u32]) -> Vec<u64> {
+        points(matches, |m| match m {
+            0 => 0,
+            m => {
+                let (mut a, mut b) = (1u64, 1);
+                for _ in 1..m {
+                    (a, b) = (b, a.saturating_add(b));
+                }
+                b
+            }
+        })
+    }
+}
+
+/// Scratches every card once in order, each copy of card `i` adding a copy of every card
+/// `targets(i, matches[i])` returns. No card's count goes over `cap`.
+///
+/// When cards only win copies of later cards, each card's count is final by the time the pass
+/// reaches it, so one pass is enough however large the counts get: O(cards × matches).
+fn cascade<I>(matches: &[u32], cap: u64, targets: impl Fn(usize, u32) -> I) -> Vec<u64>
+where
+    I: Iterator<Item = usize>,
+{
+    let mut counts = vec![1.min(cap); matches.len()];
+    for (i, &won) in matches.iter().enumerate() {
+        let copies = counts[i];
+        for j in targets(i, won) {
+            counts[j] = counts[j].saturating_add(copies).min(cap);
+        }
+    }
+    counts
+}
+
+/// Returns how many of each card you end up with under the part 2 rules, originals included.
+pub fn copy_counts(matches: &[u32]) -> Vec<u64> {
+    Copies.per_card(matches)
+}
+
+impl ScoringRule for Copies {
+    fn per_card(&self, matches: &[u32]) -> Vec<u64> {
+        let cards = matches.len();
+        cascade(matches, u64::MAX, |i, won| {
+            (i + 1..i + 1 + won as usize).take_while(move |&j| j < cards)
+        })
+    }
+}
+
+impl ScoringRule for WrappingCopies {
+    fn per_card(&self, matches: &[u32]) -> Vec<u64> {
+        let cards = matches.len();
+        cascade(matches, u64::MAX, |i, won| {
+            (i + 1..i + 1 + won as usize).map(move |j| j % cards)
+        })
+    }
+}
+
+impl ScoringRule for CappedCopies {
+    fn per_card(&self, matches: &[u32]) -> Vec<u64> {
+        let cards = matches.len();
+        cascade(matches, self.cap, |i, won| {
+            (i + 1..i + 1 + won as usize).take_while(move |&j| j < cards)
+        })
+    }
+}
+
+/// Returns the rule called `name`: `doubling`, `linear`, `fibonacci`, `copies`,
+/// `wrapping-copies` or `capped-copies:N`.
+pub fn parse_rule(name: &str) -> Result<Box<dyn ScoringRule>, String> {
+    match name.split_once(':') {
+        Some(("capped-copies", cap)) => {
+            let cap = cap
+                .parse()
+                .map_err(|_| format!("Expected a cap on copies, got {cap:?}"))?;
+            Ok(Box::new(CappedCopies { cap }))
+        }
+        Some(_) => Err(format!("Unknown scoring rule: {name}")),
+        None => match name {
+            "doubling" => Ok(Box::new(Doubling)),
+            "linear" => Ok(Box::new(Linear)),
+            "fibonacci" => Ok(Box::new(Fibonacci)),
+            "copies" => Ok(Box::new(Copies)),
+            "wrapping-copies" => Ok(Box::new(WrappingCopies)),
+            _ => Err(format!("Unknown scoring rule: {name}")),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Match counts of the puzzle's example cards.
+    const SAMPLE: [u32; 6] = [4, 2, 2, 1, 0, 0];
+
+    #[test]
+    fn points_rules() {
+        assert_eq!(Doubling.per_card(&SAMPLE), vec![8, 2, 2, 1, 0, 0]);
+        assert_eq!(Doubling.score(&SAMPLE), 13);
+        assert_eq!(Linear.score(&SAMPLE), 9);
+        assert_eq!(
+            Fibonacci.per_card(&[0, 1, 2, 3, 4, 5]),
+            vec![0, 1, 2, 3, 5, 8]
+        );
+        assert_eq!(Fibonacci.score(&SAMPLE), 5 + 2 + 2 + 1);
+        assert_eq!(Doubling.per_card(&[64, 65]), vec![1 << 63, u64::MAX]);
+        assert_eq!(Doubling.score(&[65, 1]), u64::MAX);
+        assert_eq!(Doubling.score(&[64, 64]), u64::MAX);
+        assert_eq!(Fibonacci.score(&[200, 200]), u64::MAX);
+    }
+
+    #[test]
+    fn copy_rules() {
+        assert_eq!(copy_counts(&SAMPLE), vec![1, 2, 4, 8, 14, 1]);
+        assert_eq!(Copies.score(&SAMPLE), 30);
+
+        // Wins running off the end of the table are dropped
+        assert_eq!(copy_counts(&[0, 3, 5]), vec![1, 1, 2]);
+        assert_eq!(copy_counts(&[]), Vec::<u64>::new());
+
+        // Counts grow like the Fibonacci numbers and pass u64::MAX near card 93
+        assert_eq!(Copies.score(&[2; 100]), u64::MAX);
+    }
+
+    #[test]
+    fn wrapping_copies() {
+        // Card 3's win wraps to card 1, which has already been scratched
+        assert_eq!(WrappingCopies.per_card(&[1, 0, 1]), vec![2, 2, 1]);
+        // Card 2's three wins go to 3, 1 and 2
+        assert_eq!(WrappingCopies.per_card(&[0, 3, 0]), vec![2, 2, 2]);
+        assert_eq!(WrappingCopies.per_card(&SAMPLE), copy_counts(&SAMPLE));
+        assert_eq!(WrappingCopies.score(&[]), 0);
+    }
+
+    #[test]
+    fn capped_copies() {
+        assert_eq!(
+            CappedCopies { cap: 5 }.per_card(&SAMPLE),
+            vec![1, 2, 4, 5, 5, 1]
+        );
+        assert_eq!(CappedCopies { cap: u64::MAX }.score(&SAMPLE), 30);
+        assert_eq!(CappedCopies { cap: 0 }.score(&SAMPLE), 0);
+    }
+
+    #[test]
+    fn rule_names() {
+        assert_eq!(parse_rule("linear").unwrap().score(&SAMPLE), 9);
+        assert_eq!(parse_rule("capped-copies:5").unwrap().score(&SAMPLE), 18);
+        assert_eq!(parse_rule("wrapping-copies").unwrap().score(&SAMPLE), 30);
+        assert!(parse_rule("capped-copies:x").is_err());
+        assert!(parse_rule("doubling:2").is_err());
+        assert!(parse_rule("tripling").is_err());
+    }
+}
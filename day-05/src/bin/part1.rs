@@ -73,14 +73,18 @@ fn solve(input: &str) -> i64 {
 
     let location_finder = |acc, x: &Map| x.get_destination(acc);
 
-    nums.inspect(|a| println!("Prior Location: {}", a))
+    nums.inspect(|a| tracing::debug!("Prior Location: {}", a))
         .map(|a| almanac.iter().fold(a, location_finder))
-        .inspect(|a| println!("Location: {}", a))
+        .inspect(|a| tracing::debug!("Location: {}", a))
         .min()
         .expect("num")
 }
 
 fn main() {
+    let args = common::init_tracing(std::env::args().skip(1).collect());
+    if let Some(arg) = args.first() {
+        panic!("Unknown argument: {arg}");
+    }
     let input = include_str!("./input.txt");
     println!("{}", solve(input));
 }